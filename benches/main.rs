@@ -1,4 +1,5 @@
 #![feature(test)]
+// Every bench seeds the global generator with `seed(0)` first, so numbers don't drift with the starting state
 
 extern crate test;
 
@@ -6,6 +7,72 @@ use test::Bencher;
 
 #[bench]
 fn main(b: &mut Bencher) {
+	hel_random::seed(0);
+
 	b.iter(hel_random::u64);
 	assert!(hel_random::u64() > 0);
 }
+
+#[bench]
+fn bool_fast(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(hel_random::bool);
+}
+
+#[bench]
+fn bool_decorrelated(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(hel_random::bool_decorrelated);
+}
+
+#[bench]
+fn byte_array_fill(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(hel_random::random_byte_array::<32>);
+}
+
+#[bench]
+fn byte_array_per_byte(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(|| -> [u8; 32] { std::array::from_fn(|_| hel_random::u8()) });
+}
+
+#[bench]
+fn fill_range_bulk(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	let mut buf = [0u32; 1024];
+
+	b.iter(|| hel_random::fill_range(&mut buf, 0, 1000));
+}
+
+#[bench]
+fn fill_range_per_element(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	let mut buf = [0u32; 1024];
+
+	b.iter(|| {
+		for x in &mut buf {
+			*x = hel_random::range(0, 1000) as u32;
+		}
+	});
+}
+
+#[bench]
+fn normal_vec_bulk(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(|| hel_random::normal_vec(0.0, 1.0, 1024));
+}
+
+#[bench]
+fn normal_vec_per_element(b: &mut Bencher) {
+	hel_random::seed(0);
+
+	b.iter(|| -> Vec<f64> { (0..1024).map(|_| hel_random::normal(0.0, 1.0)).collect() });
+}
@@ -0,0 +1,651 @@
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+	ops::Range,
+};
+
+use crate::{Global, Random, Rng, Source};
+
+/// Uniform length in `range`
+#[inline]
+fn len_in<S: Source>(source: &mut S, range: Range<usize>) -> usize {
+	assert!(!range.is_empty(), "length range is empty: {range:?}");
+
+	range.start + source.below((range.end - range.start) as u64) as usize
+}
+
+/// Will generate a [`Vec`] of random `T`s with a length uniformly drawn from `len_range`
+///
+/// # Panics
+/// If `len_range` is empty
+///
+/// # Example
+/// ```
+/// use hel_random::random_vec;
+///
+/// let v: Vec<u32> = random_vec(1..10);
+///
+/// assert!((1..10).contains(&v.len()));
+/// ```
+pub fn random_vec<T: Random>(len_range: Range<usize>) -> Vec<T> {
+	random_vec_from(&mut Global, len_range)
+}
+
+pub(crate) fn random_vec_from<S: Source, T: Random>(
+	source: &mut S,
+	len_range: Range<usize>,
+) -> Vec<T> {
+	let len = len_in(source, len_range);
+
+	(0..len).map(|_| T::random_from(source)).collect()
+}
+
+/// Will generate `len` random `T`s in ascending order, duplicates included
+///
+/// # Example
+/// ```
+/// use hel_random::sorted_vec;
+///
+/// let haystack: Vec<u16> = sorted_vec(100);
+///
+/// assert_eq!(haystack.len(), 100);
+/// assert!(haystack.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn sorted_vec<T: Random + Ord>(len: usize) -> Vec<T> {
+	sorted_vec_from(&mut Global, len)
+}
+
+pub(crate) fn sorted_vec_from<S: Source, T: Random + Ord>(source: &mut S, len: usize) -> Vec<T> {
+	let mut v = random_vec_from(source, len..len + 1);
+	v.sort_unstable();
+
+	v
+}
+
+/// Will generate `len` random `T`s in strictly ascending order, with duplicates removed
+///
+/// The result is shorter than `len` when values collide, which is likely for small types
+///
+/// # Example
+/// ```
+/// use hel_random::sorted_unique_vec;
+///
+/// let keys: Vec<u64> = sorted_unique_vec(100);
+/// assert!(keys.windows(2).all(|w| w[0] < w[1]));
+///
+/// let bytes: Vec<u8> = sorted_unique_vec(1000);
+/// assert!(bytes.len() <= 256);
+/// ```
+pub fn sorted_unique_vec<T: Random + Ord>(len: usize) -> Vec<T> {
+	sorted_unique_vec_from(&mut Global, len)
+}
+
+pub(crate) fn sorted_unique_vec_from<S: Source, T: Random + Ord>(
+	source: &mut S,
+	len: usize,
+) -> Vec<T> {
+	let mut v = sorted_vec_from(source, len);
+	v.dedup();
+
+	v
+}
+
+/// Will fill `out` round-robin from `streams` generators, with `streams >= 2` neighbors never come
+/// from the same stream
+///
+/// The streams are [`Rng::jump`] apart from a fresh seed off the global generator, so they are
+/// guaranteed not to overlap: element `i` comes from stream `i % streams`. A single stream is just
+/// one [`Rng`] filling the whole slice. Setting up costs `256 * (streams - 1)` steps on top of the
+/// draws, so it only pays off for large slices.
+///
+/// # Panics
+/// If `streams == 0`
+///
+/// # Example
+/// ```
+/// use hel_random::fill_interleaved;
+///
+/// let mut keys = [0u64; 64];
+/// fill_interleaved(&mut keys, 4);
+///
+/// assert!(keys.windows(2).all(|w| w[0] != w[1]));
+/// ```
+pub fn fill_interleaved<T: Random>(out: &mut [T], streams: usize) {
+	fill_interleaved_from(&mut Global, out, streams)
+}
+
+pub(crate) fn fill_interleaved_from<S: Source, T: Random>(
+	source: &mut S,
+	out: &mut [T],
+	streams: usize,
+) {
+	assert!(streams > 0, "need at least one stream");
+
+	let mut rng = Rng::from_seed(source.next_u64());
+	let mut gens = Vec::with_capacity(streams.min(out.len()));
+
+	// Streams beyond the length of `out` would never be drawn from
+	for _ in 0..streams.min(out.len()) {
+		gens.push(rng.clone());
+		rng.jump();
+	}
+
+	for (i, x) in out.iter_mut().enumerate() {
+		*x = T::random_from(&mut gens[i % streams]);
+	}
+}
+
+/// Will generate a [`HashMap`] of random entries, drawing the number of entries from `len_range`
+///
+/// Colliding keys are deduplicated, so for small key types the map may end up shorter than the drawn length
+///
+/// # Panics
+/// If `len_range` is empty
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+///
+/// use hel_random::random_hashmap;
+///
+/// let map: HashMap<u64, bool> = random_hashmap(5..6);
+///
+/// assert_eq!(map.len(), 5);
+/// ```
+pub fn random_hashmap<K: Random + Hash + Eq, V: Random>(len_range: Range<usize>) -> HashMap<K, V> {
+	random_hashmap_from(&mut Global, len_range)
+}
+
+pub(crate) fn random_hashmap_from<S: Source, K: Random + Hash + Eq, V: Random>(
+	source: &mut S,
+	len_range: Range<usize>,
+) -> HashMap<K, V> {
+	let len = len_in(source, len_range);
+
+	(0..len)
+		.map(|_| (K::random_from(source), V::random_from(source)))
+		.collect()
+}
+
+/// Will generate a [`HashSet`] by inserting random `T`s until it holds `target_len` elements,
+/// giving up after `max_tries` insertions
+///
+/// Small types may not have enough distinct values (a `u8` has only 256), so the returned set can be
+/// shorter than `target_len`
+///
+/// # Example
+/// ```
+/// use hel_random::random_set;
+///
+/// let ids = random_set::<u64>(100, 1_000);
+/// assert_eq!(ids.len(), 100);
+///
+/// let flags = random_set::<bool>(3, 100);
+/// assert!(flags.len() <= 2);
+/// ```
+pub fn random_set<T: Random + Hash + Eq>(target_len: usize, max_tries: usize) -> HashSet<T> {
+	random_set_from(&mut Global, target_len, max_tries)
+}
+
+pub(crate) fn random_set_from<S: Source, T: Random + Hash + Eq>(
+	source: &mut S,
+	target_len: usize,
+	max_tries: usize,
+) -> HashSet<T> {
+	let mut set = HashSet::with_capacity(target_len.min(max_tries));
+
+	for _ in 0..max_tries {
+		if set.len() >= target_len {
+			break;
+		}
+
+		set.insert(T::random_from(source));
+	}
+
+	set
+}
+
+/// Will fill `out` with mutually distinct random `T`s, giving up after `max_tries` draws
+///
+/// Returns `false` if not enough distinct values came up, `out` is left untouched then.
+/// Small types may not have enough distinct values at all (a `u8` has only 256), so filling
+/// a longer slice always fails. The values end up in a uniformly random order.
+///
+/// # Example
+/// ```
+/// use hel_random::fill_unique;
+///
+/// let mut ids = [0u64; 100];
+/// assert!(fill_unique(&mut ids, 1_000));
+///
+/// let mut flags = [false; 3];
+/// assert!(!fill_unique(&mut flags, 100));
+/// ```
+pub fn fill_unique<T: Random + Eq + Hash>(out: &mut [T], max_tries: usize) -> bool {
+	fill_unique_from(&mut Global, out, max_tries)
+}
+
+pub(crate) fn fill_unique_from<S: Source, T: Random + Eq + Hash>(
+	source: &mut S,
+	out: &mut [T],
+	max_tries: usize,
+) -> bool {
+	// When every value was first drawn, the set's own iteration order changes with its hasher keys
+	let mut first_draw = HashMap::with_capacity(out.len().min(max_tries));
+
+	for i in 0..max_tries {
+		if first_draw.len() >= out.len() {
+			break;
+		}
+
+		first_draw.entry(T::random_from(source)).or_insert(i);
+	}
+
+	if first_draw.len() < out.len() {
+		return false;
+	}
+
+	let mut values: Vec<(T, usize)> = first_draw.into_iter().collect();
+	values.sort_unstable_by_key(|&(_, i)| i);
+
+	for (slot, (value, _)) in out.iter_mut().zip(values) {
+		*slot = value;
+	}
+
+	// Likelier values tend to be drawn first, this makes the order independent of them
+	source.shuffle(out);
+
+	true
+}
+
+/// Will generate a mask of `n` booleans with exactly `k` of them `true`,
+/// every choice of the `k` positions equally likely
+///
+/// The positions are picked with a partial Fisher–Yates shuffle, `k` bounded draws and no rejection
+///
+/// # Panics
+/// If `k > n`
+///
+/// # Example
+/// ```
+/// use hel_random::random_mask_with_k;
+///
+/// let features = random_mask_with_k(10, 3);
+///
+/// assert_eq!(features.len(), 10);
+/// assert_eq!(features.iter().filter(|&&f| f).count(), 3);
+/// ```
+pub fn random_mask_with_k(n: usize, k: usize) -> Vec<bool> {
+	random_mask_with_k_from(&mut Global, n, k)
+}
+
+pub(crate) fn random_mask_with_k_from<S: Source>(source: &mut S, n: usize, k: usize) -> Vec<bool> {
+	assert!(k <= n, "can't set {k} out of {n} positions");
+
+	let mut positions: Vec<usize> = (0..n).collect();
+	let mut mask = vec![false; n];
+
+	for i in 0..k {
+		let j = i + source.below((n - i) as u64) as usize;
+		positions.swap(i, j);
+
+		mask[positions[i]] = true;
+	}
+
+	mask
+}
+
+/// Will generate the edge list of an Erdős–Rényi graph: every unordered pair of the `n` vertices
+/// is connected with probability `edge_prob`
+///
+/// Edges are `(a, b)` with `a < b`, in lexicographic order. There are no self loops.
+///
+/// # Panics
+/// If `edge_prob` is not in `[0, 1]`
+///
+/// # Example
+/// ```
+/// use hel_random::random_graph;
+///
+/// let edges = random_graph(10, 0.3);
+///
+/// assert!(edges.iter().all(|&(a, b)| a < b && b < 10));
+/// assert_eq!(random_graph(4, 1.0).len(), 6);
+/// ```
+pub fn random_graph(n: usize, edge_prob: f64) -> Vec<(usize, usize)> {
+	random_graph_from(&mut Global, n, edge_prob)
+}
+
+pub(crate) fn random_graph_from<S: Source>(
+	source: &mut S,
+	n: usize,
+	edge_prob: f64,
+) -> Vec<(usize, usize)> {
+	assert!(
+		(0.0..=1.0).contains(&edge_prob),
+		"edge probability must be in [0, 1]: {edge_prob}"
+	);
+
+	let mut edges = Vec::new();
+
+	for a in 0..n {
+		for b in a + 1..n {
+			if f64::random_from(source) < edge_prob {
+				edges.push((a, b));
+			}
+		}
+	}
+
+	edges
+}
+
+/// Will generate the edge list of a uniformly random labeled tree on `n` vertices
+///
+/// Every one of the `n^(n - 2)` trees is equally likely: a random Prüfer sequence is decoded into edges
+/// in linear time. Unlike [`random_graph`] the result is always connected, with exactly `n - 1` edges.
+/// Edges are `(a, b)` with `a < b`, in lexicographic order.
+///
+/// # Panics
+/// If `n` is `0`
+///
+/// # Example
+/// ```
+/// use hel_random::random_tree;
+///
+/// let edges = random_tree(10);
+///
+/// assert_eq!(edges.len(), 9);
+/// assert!(edges.iter().all(|&(a, b)| a < b && b < 10));
+/// assert!(random_tree(1).is_empty());
+/// ```
+pub fn random_tree(n: usize) -> Vec<(usize, usize)> {
+	random_tree_from(&mut Global, n)
+}
+
+pub(crate) fn random_tree_from<S: Source>(source: &mut S, n: usize) -> Vec<(usize, usize)> {
+	assert!(n >= 1, "a tree needs at least one vertex");
+
+	let code: Vec<usize> = (0..n.saturating_sub(2))
+		.map(|_| source.below(n as u64) as usize)
+		.collect();
+
+	// Every vertex is a leaf until the sequence says otherwise
+	let mut degree = vec![1usize; n];
+	for &v in &code {
+		degree[v] += 1;
+	}
+
+	let mut edges = Vec::with_capacity(n - 1);
+	let mut ptr = degree.iter().position(|&d| d == 1).unwrap_or(0);
+	let mut leaf = ptr;
+
+	for &v in &code {
+		edges.push((leaf.min(v), leaf.max(v)));
+
+		degree[v] -= 1;
+
+		// A vertex that just became a leaf behind the pointer is the smallest one
+		if degree[v] == 1 && v < ptr {
+			leaf = v;
+		} else {
+			ptr += 1;
+			while degree[ptr] != 1 {
+				ptr += 1;
+			}
+			leaf = ptr;
+		}
+	}
+
+	if n > 1 {
+		edges.push((leaf, n - 1));
+	}
+
+	edges.sort_unstable();
+	edges
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vec_lengths() {
+		let mut seen = [false; 4];
+
+		for _ in 0..1_000 {
+			let v: Vec<u8> = random_vec(2..6);
+			seen[v.len() - 2] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert!(random_vec::<u64>(0..1).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn vec_empty_range() {
+		let _: Vec<u8> = random_vec(3..3);
+	}
+
+	#[test]
+	fn sorted_vecs() {
+		let v: Vec<i8> = sorted_vec(1000);
+		assert_eq!(v.len(), 1000);
+		assert!(v.windows(2).all(|w| w[0] <= w[1]));
+		assert!(v.windows(2).any(|w| w[0] == w[1]));
+
+		let u: Vec<i8> = sorted_unique_vec(1000);
+		assert!(u.windows(2).all(|w| w[0] < w[1]));
+		assert!(u.len() <= 256);
+
+		assert!(sorted_vec::<u32>(0).is_empty());
+		assert!(sorted_unique_vec::<u32>(0).is_empty());
+	}
+
+	#[test]
+	fn interleaved_streams() {
+		let mut out = [0u64; 100];
+		fill_interleaved(&mut out, 3);
+
+		let mut sorted = out.to_vec();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(sorted.len(), 100);
+
+		fill_interleaved::<u8>(&mut [], 5);
+		fill_interleaved(&mut out[..1], 1_000_000);
+	}
+
+	#[test]
+	#[cfg(feature = "test-hooks")]
+	fn interleaved_single_stream() {
+		use crate::test_utils::set_next_values;
+
+		// The seed is the only global draw, all of `out` then comes from that one generator
+		set_next_values(vec![42]);
+
+		let mut out = [0u64; 8];
+		fill_interleaved(&mut out, 1);
+
+		let mut rng = Rng::from_seed(42);
+		assert!(out.iter().all(|&x| x == rng.next_u64()));
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = Rng::from_seed(55);
+
+			let mut interleaved = [0u32; 10];
+			rng.fill_interleaved(&mut interleaved, 3);
+
+			let mut unique = [0u16; 10];
+			assert!(rng.fill_unique(&mut unique, 100));
+
+			let mut set: Vec<u8> = rng.random_set(10, 100).into_iter().collect();
+			set.sort();
+
+			let mut map: Vec<(u8, bool)> = rng.random_hashmap(5..10).into_iter().collect();
+			map.sort();
+
+			(
+				rng.random_vec::<u32>(1..10),
+				rng.sorted_vec::<u16>(10),
+				rng.sorted_unique_vec::<u8>(10),
+				interleaved,
+				map,
+				set,
+				unique,
+				rng.random_mask_with_k(10, 4),
+				rng.random_graph(8, 0.3),
+				rng.random_tree(8),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	#[should_panic]
+	fn interleaved_no_streams() {
+		fill_interleaved(&mut [0u8; 4], 0);
+	}
+
+	#[test]
+	fn set_respects_tries() {
+		assert!(random_set::<u64>(10, 0).is_empty());
+		assert_eq!(random_set::<u64>(0, 10).len(), 0);
+		assert_eq!(random_set::<u64>(10, 10).len(), 10);
+
+		// The whole `u8` space is reachable with plenty of tries, but no more
+		assert_eq!(random_set::<u8>(1_000, 100_000).len(), 256);
+	}
+
+	#[test]
+	fn unique_fills() {
+		let mut ids = [0u32; 500];
+		assert!(fill_unique(&mut ids, 10_000));
+
+		let distinct: HashSet<u32> = ids.iter().copied().collect();
+		assert_eq!(distinct.len(), 500);
+
+		// The whole `u8` space fits, one more doesn't
+		let mut bytes = [0u8; 256];
+		assert!(fill_unique(&mut bytes, 100_000));
+
+		let mut too_many = [7u8; 257];
+		assert!(!fill_unique(&mut too_many, 100_000));
+		assert!(too_many.iter().all(|&b| b == 7));
+
+		assert!(fill_unique::<u64>(&mut [], 0));
+	}
+
+	#[test]
+	fn mask_positions_are_uniform() {
+		const TRIES: usize = 20_000;
+
+		let mut counts = [0usize; 8];
+		let mut masks = HashSet::new();
+
+		for _ in 0..TRIES {
+			let mask = random_mask_with_k(8, 3);
+			assert_eq!(mask.iter().filter(|&&b| b).count(), 3);
+
+			mask.iter()
+				.enumerate()
+				.for_each(|(i, &b)| counts[i] += b as usize);
+			masks.insert(mask);
+		}
+
+		// Every one of the `8 choose 3` masks comes up
+		assert_eq!(masks.len(), 56);
+
+		let freq = counts.map(|c| c as f64 / TRIES as f64);
+		println!("Position frequencies: {freq:?}");
+		assert!(freq.iter().all(|f| (f - 3.0 / 8.0).abs() < 0.02));
+
+		assert_eq!(random_mask_with_k(4, 4), [true; 4]);
+		assert!(random_mask_with_k(0, 0).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn mask_too_many() {
+		random_mask_with_k(3, 4);
+	}
+
+	#[test]
+	fn trees_are_uniform_and_connected() {
+		const TRIES: usize = 32_000;
+
+		let mut trees = HashMap::new();
+
+		for _ in 0..TRIES {
+			let edges = random_tree(4);
+			assert_eq!(edges.len(), 3);
+
+			// Union-find, every edge has to join two components
+			let mut parent: Vec<usize> = (0..4).collect();
+			let root = |parent: &[usize], mut v: usize| {
+				while parent[v] != v {
+					v = parent[v];
+				}
+				v
+			};
+
+			for &(a, b) in &edges {
+				assert!(a < b);
+
+				let (ra, rb) = (root(&parent, a), root(&parent, b));
+				assert_ne!(ra, rb);
+				parent[ra] = rb;
+			}
+
+			*trees.entry(edges).or_insert(0usize) += 1;
+		}
+
+		// Cayley: `4^2` labeled trees
+		assert_eq!(trees.len(), 16);
+		assert!(trees
+			.values()
+			.all(|&c| (c as f64 / TRIES as f64 - 1.0 / 16.0).abs() < 0.01));
+
+		assert_eq!(random_tree(2), [(0, 1)]);
+		assert_eq!(random_tree(1000).len(), 999);
+	}
+
+	#[test]
+	#[should_panic]
+	fn tree_without_vertices() {
+		random_tree(0);
+	}
+
+	#[test]
+	fn graph_density() {
+		assert!(random_graph(50, 0.0).is_empty());
+		assert_eq!(random_graph(50, 1.0).len(), 50 * 49 / 2);
+		assert!(random_graph(0, 1.0).is_empty());
+		assert!(random_graph(1, 1.0).is_empty());
+
+		let edges = random_graph(200, 0.25);
+		let density = edges.len() as f64 / (200 * 199 / 2) as f64;
+		println!("Density: {density}");
+		assert!((density - 0.25).abs() < 0.02);
+		assert!(edges.windows(2).all(|w| w[0] < w[1]));
+	}
+
+	#[test]
+	#[should_panic]
+	fn graph_invalid_probability() {
+		random_graph(3, 1.5);
+	}
+
+	#[test]
+	fn hashmap_dedups() {
+		let map: HashMap<bool, u8> = random_hashmap(100..101);
+		assert!(map.len() <= 2);
+
+		let map: HashMap<u128, u8> = random_hashmap(50..60);
+		assert!((50..60).contains(&map.len()));
+	}
+}
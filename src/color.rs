@@ -0,0 +1,100 @@
+//! Random colors for test visuals and placeholder data
+
+use crate::{Global, Random, Source};
+
+/// Will generate a random `(r, g, b)` color
+///
+/// # Example
+/// ```
+/// let (r, g, b) = hel_random::color::rgb();
+/// println!("#{r:02x}{g:02x}{b:02x}");
+/// ```
+#[inline]
+pub fn rgb() -> (u8, u8, u8) {
+	Global.rgb()
+}
+
+#[inline]
+pub(crate) fn rgb_from<S: Source>(source: &mut S) -> (u8, u8, u8) {
+	(
+		u8::random_from(source),
+		u8::random_from(source),
+		u8::random_from(source),
+	)
+}
+
+/// Will generate a random `(r, g, b, a)` color
+///
+/// # Example
+/// ```
+/// let (r, g, b, a) = hel_random::color::rgba();
+/// println!("#{r:02x}{g:02x}{b:02x}{a:02x}");
+/// ```
+#[inline]
+pub fn rgba() -> (u8, u8, u8, u8) {
+	Global.rgba()
+}
+
+#[inline]
+pub(crate) fn rgba_from<S: Source>(source: &mut S) -> (u8, u8, u8, u8) {
+	let (r, g, b) = rgb_from(source);
+	(r, g, b, u8::random_from(source))
+}
+
+/// Will generate a random `(hue, saturation, lightness)` color
+///
+/// Hue is in `[0, 360)`, saturation and lightness are in `[0, 1)`
+///
+/// # Example
+/// ```
+/// let (h, s, l) = hel_random::color::hsl();
+///
+/// assert!((0.0..360.0).contains(&h));
+/// assert!((0.0..1.0).contains(&s));
+/// assert!((0.0..1.0).contains(&l));
+/// ```
+#[inline]
+pub fn hsl() -> (f64, f64, f64) {
+	Global.hsl()
+}
+
+#[inline]
+pub(crate) fn hsl_from<S: Source>(source: &mut S) -> (f64, f64, f64) {
+	(
+		f64::random_from(source) * 360.0,
+		f64::random_from(source),
+		f64::random_from(source),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hsl_ranges() {
+		for _ in 0..10_000 {
+			let (h, s, l) = hsl();
+
+			assert!((0.0..360.0).contains(&h));
+			assert!((0.0..1.0).contains(&s));
+			assert!((0.0..1.0).contains(&l));
+		}
+	}
+
+	#[test]
+	fn rgb_varies() {
+		let colors: std::collections::HashSet<_> = (0..100).map(|_| rgba()).collect();
+		assert!(colors.len() > 90);
+	}
+
+	#[test]
+	fn seeded_colors() {
+		let run = || {
+			let mut rng = crate::Rng::from_seed(21);
+			(rng.rgb(), rng.rgba(), rng.hsl())
+		};
+
+		assert_eq!(run(), run());
+	}
+}
@@ -0,0 +1,942 @@
+use crate::{standard_normal_pair_from, unit_open, Global, Random, Rng, Source};
+
+/// Will generate a uniform [`f64`] in `[low, high)`
+///
+/// # Panics
+/// If `low >= high` or the width of the range isn't finite
+///
+/// # Example
+/// ```
+/// use hel_random::range_f64;
+///
+/// let t = range_f64(-40.0, 50.0);
+///
+/// assert!((-40.0..50.0).contains(&t));
+/// ```
+#[inline]
+pub fn range_f64(low: f64, high: f64) -> f64 {
+	range_f64_from(&mut Global, low, high)
+}
+
+pub(crate) fn range_f64_from<S: Source>(source: &mut S, low: f64, high: f64) -> f64 {
+	assert!(low < high, "invalid range: [{low}, {high})");
+
+	let width = high - low;
+	assert!(width.is_finite(), "range is too wide: [{low}, {high})");
+
+	loop {
+		let r = low + width * f64::random_from(source);
+
+		// Rounding can land exactly on `high`
+		if r < high {
+			return r;
+		}
+	}
+}
+
+/// Will generate a uniform [`f64`] in the closed interval `[0, 1]`
+///
+/// The 2^53 possible values are evenly spaced from `0` to `1` both included,
+/// so either endpoint comes up with a probability of `2^-53`.
+///
+/// # Example
+/// ```
+/// use hel_random::closed01;
+///
+/// let u = closed01();
+///
+/// assert!((0.0..=1.0).contains(&u));
+/// ```
+#[inline]
+pub fn closed01() -> f64 {
+	Global.closed01()
+}
+
+#[inline]
+pub(crate) fn unit_closed(x: u64) -> f64 {
+	(x >> 11) as f64 * (1.0 / ((1u64 << 53) - 1) as f64)
+}
+
+/// Will generate a uniform [`f64`] in `[low, high]`, both endpoints reachable
+///
+/// Scales a [`closed01`] draw, so `high` comes up exactly with a probability of `2^-53`,
+/// same as `low`. The draw has 53 bits of resolution spread over the width, so for wide ranges most
+/// floats in between are skipped, and the result is clamped to `high` in case rounding overshoots.
+///
+/// # Panics
+/// If `low > high` or the width of the range isn't finite
+///
+/// # Example
+/// ```
+/// use hel_random::range_f64_inclusive;
+///
+/// let alpha = range_f64_inclusive(0.0, 1.0);
+///
+/// assert!((0.0..=1.0).contains(&alpha));
+/// assert_eq!(range_f64_inclusive(2.5, 2.5), 2.5);
+/// ```
+#[inline]
+pub fn range_f64_inclusive(low: f64, high: f64) -> f64 {
+	range_f64_inclusive_from(&mut Global, low, high)
+}
+
+pub(crate) fn range_f64_inclusive_from<S: Source>(source: &mut S, low: f64, high: f64) -> f64 {
+	assert!(low <= high, "invalid range: [{low}, {high}]");
+
+	let width = high - low;
+	assert!(width.is_finite(), "range is too wide: [{low}, {high}]");
+
+	range_closed_from(unit_closed(source.next_u64()), low, width, high)
+}
+
+#[inline]
+fn range_closed_from(u: f64, low: f64, width: f64, high: f64) -> f64 {
+	if u == 1.0 {
+		return high;
+	}
+
+	(low + width * u).min(high)
+}
+
+/// Will generate a random walk of `steps` positions starting from `0`, each adding a uniform step
+/// in `[-step_size, step_size)` to the previous one
+///
+/// # Panics
+/// If `step_size` is negative or not finite
+///
+/// # Example
+/// ```
+/// use hel_random::random_walk;
+///
+/// let terrain = random_walk(100, 0.5);
+///
+/// assert_eq!(terrain.len(), 100);
+/// assert!(terrain.windows(2).all(|w| (w[1] - w[0]).abs() <= 0.5));
+/// ```
+pub fn random_walk(steps: usize, step_size: f64) -> Vec<f64> {
+	walk_from(&mut Global, steps, step_size)
+}
+
+/// Will generate a [`random_walk`] with a temporary [`Rng`] seeded from `seed`, the same seed
+/// always gives the same walk
+///
+/// # Panics
+/// Same as [`random_walk`]
+///
+/// # Example
+/// ```
+/// use hel_random::seeded_random_walk;
+///
+/// assert_eq!(seeded_random_walk(3, 10, 1.0), seeded_random_walk(3, 10, 1.0));
+/// ```
+pub fn seeded_random_walk(seed: u64, steps: usize, step_size: f64) -> Vec<f64> {
+	walk_from(&mut Rng::from_seed(seed), steps, step_size)
+}
+
+pub(crate) fn walk_from<S: Source>(source: &mut S, steps: usize, step_size: f64) -> Vec<f64> {
+	assert!(
+		step_size >= 0.0 && step_size.is_finite(),
+		"invalid step size: {step_size}"
+	);
+
+	let mut position = 0.0;
+
+	(0..steps)
+		.map(|_| {
+			// `2u - 1` is exact and in `[-1, 1)`
+			position += step_size * (2.0 * f64::random_from(source) - 1.0);
+			position
+		})
+		.collect()
+}
+
+/// Will generate a uniform [`f64`] in `[low, high)` rounded to `decimals` decimal places
+///
+/// Gives values like `3.14` instead of `3.141592653589793` for nicer fixtures.
+/// Rounding happens after the draw, so the result can land up to half a step outside of the range,
+/// e.g. on `high` itself. When `decimals` is beyond what an [`f64`] can represent for the value
+/// it is returned as is.
+///
+/// # Panics
+/// Same as [`range_f64`]
+///
+/// # Example
+/// ```
+/// use hel_random::rounded_f64;
+///
+/// let price = rounded_f64(1.0, 100.0, 2);
+/// let cents = price * 100.0;
+///
+/// assert!((cents - cents.round()).abs() < 1e-9);
+/// assert_eq!(rounded_f64(1.0, 100.0, 0).fract(), 0.0);
+/// ```
+pub fn rounded_f64(low: f64, high: f64, decimals: u32) -> f64 {
+	rounded_f64_from(&mut Global, low, high, decimals)
+}
+
+pub(crate) fn rounded_f64_from<S: Source>(
+	source: &mut S,
+	low: f64,
+	high: f64,
+	decimals: u32,
+) -> f64 {
+	let r = range_f64_from(source, low, high);
+	let scale = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+	let scaled = r * scale;
+
+	// Past 2^53 every f64 is an integer already
+	if !scaled.is_finite() || scaled.abs() >= (1u64 << 53) as f64 {
+		return r;
+	}
+
+	scaled.round() / scale
+}
+
+/// Will generate a symmetric triangular [`f64`] on `[center - spread, center + spread]`
+///
+/// The average of two uniforms, so values pile up around `center` with a linear falloff.
+/// A quick way to add jitter around a value.
+///
+/// # Example
+/// ```
+/// use hel_random::around;
+///
+/// let latency = around(100.0, 20.0);
+///
+/// assert!((80.0..=120.0).contains(&latency));
+/// ```
+#[inline]
+pub fn around(center: f64, spread: f64) -> f64 {
+	around_from(&mut Global, center, spread)
+}
+
+#[inline]
+pub(crate) fn around_from<S: Source>(source: &mut S, center: f64, spread: f64) -> f64 {
+	let (u1, u2) = (f64::random_from(source), f64::random_from(source));
+
+	// `u1 + u2 - 1` is triangular on `(-1, 1)`
+	center + spread * (u1 + u2 - 1.0)
+}
+
+/// Will generate an [`f64`] in `[low, high)` uniform on a log scale
+///
+/// Every order of magnitude gets the same share of draws, e.g. `loguniform(1e-5, 1e-1)` is as likely
+/// to land in `[1e-5, 1e-4)` as in `[1e-2, 1e-1)`. The usual pick for learning rates or timeouts.
+///
+/// # Panics
+/// If `low` is not positive or `low >= high`
+///
+/// # Example
+/// ```
+/// use hel_random::loguniform;
+///
+/// let rate = loguniform(1e-5, 1e-1);
+///
+/// assert!((1e-5..1e-1).contains(&rate));
+/// ```
+pub fn loguniform(low: f64, high: f64) -> f64 {
+	loguniform_from(&mut Global, low, high)
+}
+
+pub(crate) fn loguniform_from<S: Source>(source: &mut S, low: f64, high: f64) -> f64 {
+	assert!(
+		low > 0.0,
+		"loguniform needs a positive low bound, got {low}"
+	);
+	assert!(low < high, "invalid range: [{low}, {high})");
+
+	let (ln_low, ln_high) = (low.ln(), high.ln());
+
+	loop {
+		let r = range_f64_from(source, ln_low, ln_high).exp();
+
+		// `exp` can round onto `high`, or a hair below `low`
+		if r < high {
+			return r.max(low);
+		}
+	}
+}
+
+/// Will generate a uniform [`f64`] in the open interval `(0, 1)`
+///
+/// Unlike [`f64()`](crate::f64()) it never returns `0`, so `ln` of it is always finite.
+/// All the samplers built on `ln` ([`exponential`], [`normal`], [`geometric`]) draw from this.
+///
+/// # Example
+/// ```
+/// use hel_random::open01;
+///
+/// let u = open01();
+///
+/// assert!(u > 0.0 && u < 1.0);
+/// assert!(u.ln().is_finite());
+/// ```
+#[inline]
+pub fn open01() -> f64 {
+	Global.open01()
+}
+
+/// Will sample any continuous distribution given its inverse CDF (quantile function)
+///
+/// Draws `u` from [`open01`] and returns `inv_cdf(u)`, the open interval keeps quantile functions
+/// that blow up at `0` or `1` finite.
+///
+/// # Example
+/// ```
+/// use hel_random::inverse_transform;
+///
+/// // Pareto with scale 1 and shape 3
+/// let x = inverse_transform(|u| (1.0 - u).powf(-1.0 / 3.0));
+///
+/// assert!(x >= 1.0 && x.is_finite());
+/// ```
+#[inline]
+pub fn inverse_transform<F: Fn(f64) -> f64>(inv_cdf: F) -> f64 {
+	Global.inverse_transform(inv_cdf)
+}
+
+/// Will generate an exponentially distributed [`f64`] with rate `lambda` (mean `1 / lambda`)
+///
+/// # Panics
+/// If `lambda` is not positive and finite
+///
+/// # Example
+/// ```
+/// use hel_random::exponential;
+///
+/// let wait = exponential(0.5);
+///
+/// assert!(wait > 0.0 && wait.is_finite());
+/// ```
+#[inline]
+pub fn exponential(lambda: f64) -> f64 {
+	exponential_from(&mut Global, lambda)
+}
+
+/// Will generate a normally distributed [`f64`]
+///
+/// A single Box–Muller sample, the other half of the pair is dropped.
+/// Use [`Rng::normal`](crate::Rng::normal) to make use of both.
+///
+/// # Example
+/// ```
+/// use hel_random::normal;
+///
+/// let height = normal(170.0, 10.0);
+///
+/// assert!(height.is_finite());
+/// ```
+#[inline]
+pub fn normal(mean: f64, std_dev: f64) -> f64 {
+	normal_from(&mut Global, mean, std_dev)
+}
+
+/// Will generate `len` independent normally distributed [`f64`]s
+///
+/// Both halves of every Box–Muller pair are used, so it costs half the transcendentals
+/// of calling [`normal`] `len` times.
+///
+/// # Example
+/// ```
+/// use hel_random::normal_vec;
+///
+/// let noise = normal_vec(0.0, 0.1, 1000);
+///
+/// assert_eq!(noise.len(), 1000);
+/// assert!(noise.iter().all(|n| n.is_finite()));
+/// ```
+pub fn normal_vec(mean: f64, std_dev: f64, len: usize) -> Vec<f64> {
+	normal_vec_from(&mut Global, mean, std_dev, len)
+}
+
+pub(crate) fn normal_vec_from<S: Source>(
+	source: &mut S,
+	mean: f64,
+	std_dev: f64,
+	len: usize,
+) -> Vec<f64> {
+	let mut res = vec![0.0; len];
+
+	for chunk in res.chunks_mut(2) {
+		let (a, b) = standard_normal_pair_from(source);
+
+		// The odd one out drops its second half
+		for (dst, z) in chunk.iter_mut().zip([a, b]) {
+			*dst = mean + std_dev * z;
+		}
+	}
+
+	res
+}
+
+/// Will generate the number of failures before the first success of trials with success probability `p`
+///
+/// # Panics
+/// If `p` is not in `(0, 1]`
+///
+/// # Example
+/// ```
+/// use hel_random::geometric;
+///
+/// let misses = geometric(0.25);
+///
+/// println!("Hit after {misses} misses");
+/// assert_eq!(geometric(1.0), 0);
+/// ```
+#[inline]
+pub fn geometric(p: f64) -> u64 {
+	geometric_from(&mut Global, p)
+}
+
+/// Will generate an index in `1..=n` following Zipf's law, `k` is picked proportionally to `1 / k^exponent`
+///
+/// Builds a [`ZipfDistribution`] every call, which is O(1). Keep one around to skip the validation.
+///
+/// # Panics
+/// If `n` is `0` or `exponent` is not positive and finite
+///
+/// # Example
+/// ```
+/// use hel_random::zipf;
+///
+/// let key = zipf(1000, 1.1);
+///
+/// assert!((1..=1000).contains(&key));
+/// assert_eq!(zipf(1, 2.0), 1);
+/// ```
+#[inline]
+pub fn zipf(n: usize, exponent: f64) -> usize {
+	Global.zipf(n, exponent)
+}
+
+/// A Zipf distribution over `1..=n`, for skewed access patterns like cache keys or popular items
+///
+/// Draws use the rejection-inversion method of Hörmann and Derflinger, nothing is tabulated,
+/// so both building and sampling are O(1) no matter how large `n` is.
+///
+/// # Example
+/// ```
+/// use hel_random::ZipfDistribution;
+///
+/// let popularity = ZipfDistribution::new(1_000_000, 1.0);
+///
+/// let hits = (0..1000).filter(|_| popularity.sample() == 1).count();
+/// println!("The top item got {hits} of 1000 requests");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZipfDistribution {
+	n: f64,
+	exponent: f64,
+	// `H(1.5) - 1` and `H(n + 0.5)`, where `H` integrates the hat function `x^-exponent`
+	h_integral_x1: f64,
+	h_integral_n: f64,
+	// Points that close to their integer are accepted without evaluating the hat
+	squeeze: f64,
+}
+
+impl ZipfDistribution {
+	/// Will precompute the distribution over `1..=n` with the given `exponent`
+	///
+	/// # Panics
+	/// If `n` is `0` or `exponent` is not positive and finite
+	pub fn new(n: usize, exponent: f64) -> Self {
+		assert!(n >= 1, "zipf needs at least one element");
+		assert!(
+			exponent > 0.0 && exponent.is_finite(),
+			"invalid exponent: {exponent}"
+		);
+
+		let mut zipf = Self {
+			n: n as f64,
+			exponent,
+			h_integral_x1: 0.0,
+			h_integral_n: 0.0,
+			squeeze: 0.0,
+		};
+
+		zipf.h_integral_x1 = zipf.h_integral(1.5) - 1.0;
+		zipf.h_integral_n = zipf.h_integral(zipf.n + 0.5);
+		zipf.squeeze = 2.0 - zipf.h_integral_inverse(zipf.h_integral(2.5) - zipf.h(2.0));
+
+		zipf
+	}
+
+	/// Will draw an index in `1..=n` from the global generator
+	#[inline]
+	pub fn sample(&self) -> usize {
+		self.sample_from(&mut Global)
+	}
+
+	/// Will draw an index in `1..=n` from `source`
+	pub fn sample_from<S: Source>(&self, source: &mut S) -> usize {
+		loop {
+			let u = self.h_integral_n
+				+ f64::random_from(source) * (self.h_integral_x1 - self.h_integral_n);
+			let x = self.h_integral_inverse(u);
+
+			let k = (x + 0.5).clamp(1.0, self.n).floor();
+
+			if k - x <= self.squeeze || u >= self.h_integral(k + 0.5) - self.h(k) {
+				return k as usize;
+			}
+		}
+	}
+
+	fn h(&self, x: f64) -> f64 {
+		(-self.exponent * x.ln()).exp()
+	}
+
+	fn h_integral(&self, x: f64) -> f64 {
+		let ln_x = x.ln();
+
+		expm1_over_x((1.0 - self.exponent) * ln_x) * ln_x
+	}
+
+	fn h_integral_inverse(&self, x: f64) -> f64 {
+		// Rounding can push it just past the pole
+		let t = (x * (1.0 - self.exponent)).max(-1.0);
+
+		(ln1p_over_x(t) * x).exp()
+	}
+}
+
+/// A bursty boolean stream, every output depends on the previous one (a two-state Markov chain)
+///
+/// After a `true` the next output stays `true` with probability `p_stay_true`, after a `false`
+/// it stays `false` with probability `p_stay_false`, so high probabilities give long runs.
+/// The first output is drawn from the long-run share of `true`s, as if the chain has been running for a while.
+///
+/// # Example
+/// ```
+/// use hel_random::MarkovBool;
+///
+/// // Failures come in bursts of about 5
+/// let mut failures = MarkovBool::new(0.8, 0.95);
+///
+/// let pattern: Vec<bool> = failures.by_ref().take(20).collect();
+/// println!("{pattern:?}");
+///
+/// let stuck = MarkovBool::new(1.0, 1.0).take(10).collect::<Vec<_>>();
+/// assert!(stuck.windows(2).all(|w| w[0] == w[1]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarkovBool {
+	p_stay_true: f64,
+	p_stay_false: f64,
+	last: Option<bool>,
+}
+
+impl MarkovBool {
+	/// Will create the stream with the given probabilities of repeating the last output
+	///
+	/// # Panics
+	/// If a probability is not in `[0, 1]`
+	pub fn new(p_stay_true: f64, p_stay_false: f64) -> Self {
+		assert!(
+			(0.0..=1.0).contains(&p_stay_true) && (0.0..=1.0).contains(&p_stay_false),
+			"probabilities must be in [0, 1]: {p_stay_true}, {p_stay_false}"
+		);
+
+		Self {
+			p_stay_true,
+			p_stay_false,
+			last: None,
+		}
+	}
+
+	/// Will return the next output, drawn from the global generator
+	#[inline]
+	pub fn sample(&mut self) -> bool {
+		self.sample_from(&mut Global)
+	}
+
+	/// Will return the next output, drawn from `source`
+	pub fn sample_from<S: Source>(&mut self, source: &mut S) -> bool {
+		let p_true = match self.last {
+			Some(true) => self.p_stay_true,
+			Some(false) => 1.0 - self.p_stay_false,
+			None => {
+				let (leave_true, leave_false) = (1.0 - self.p_stay_true, 1.0 - self.p_stay_false);
+
+				// Neither state is ever left, either start is as good
+				if leave_true + leave_false == 0.0 {
+					0.5
+				} else {
+					leave_false / (leave_true + leave_false)
+				}
+			}
+		};
+
+		let next = f64::random_from(source) < p_true;
+		self.last = Some(next);
+
+		next
+	}
+}
+
+impl Iterator for MarkovBool {
+	type Item = bool;
+
+	#[inline]
+	fn next(&mut self) -> Option<bool> {
+		Some(self.sample())
+	}
+}
+
+// `ln(1 + x) / x`, continuous at `0`
+fn ln1p_over_x(x: f64) -> f64 {
+	if x.abs() > 1e-8 {
+		x.ln_1p() / x
+	} else {
+		1.0 - x * (0.5 - x * (1.0 / 3.0 - 0.25 * x))
+	}
+}
+
+// `(e^x - 1) / x`, continuous at `0`
+fn expm1_over_x(x: f64) -> f64 {
+	if x.abs() > 1e-8 {
+		x.exp_m1() / x
+	} else {
+		1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + 0.25 * x))
+	}
+}
+
+pub(crate) fn exponential_from<S: Source>(source: &mut S, lambda: f64) -> f64 {
+	assert!(lambda > 0.0 && lambda.is_finite(), "invalid rate: {lambda}");
+
+	-unit_open(source.next_u64()).ln() / lambda
+}
+
+pub(crate) fn normal_from<S: Source>(source: &mut S, mean: f64, std_dev: f64) -> f64 {
+	mean + std_dev * standard_normal_pair_from(source).0
+}
+
+pub(crate) fn geometric_from<S: Source>(source: &mut S, p: f64) -> u64 {
+	assert!(p > 0.0 && p <= 1.0, "invalid probability: {p}");
+
+	if p == 1.0 {
+		return 0;
+	}
+
+	// Inversion, `as` saturates for tiny `p`
+	(unit_open(source.next_u64()).ln() / (-p).ln_1p()).floor() as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Always returns the same word
+	struct Constant(u64);
+
+	impl Source for Constant {
+		fn next_u64(&mut self) -> u64 {
+			self.0
+		}
+	}
+
+	#[test]
+	fn extreme_draws_stay_finite() {
+		for word in [0, 1, u64::MAX] {
+			let mut source = Constant(word);
+
+			let u = unit_open(word);
+			assert!(u > 0.0 && u < 1.0);
+
+			let e = exponential_from(&mut source, 1.0);
+			assert!(e.is_finite() && e > 0.0, "{e}");
+
+			let n = normal_from(&mut source, 0.0, 1.0);
+			assert!(n.is_finite(), "{n}");
+
+			geometric_from(&mut source, 1e-300);
+		}
+
+		// The smallest draw is `2^-53`
+		assert_eq!(geometric_from(&mut Constant(0), 0.5), 53);
+		assert_eq!(geometric_from(&mut Constant(u64::MAX), 0.5), 0);
+	}
+
+	#[test]
+	fn sampler_means() {
+		const TRIES: usize = 100_000;
+
+		let exp = (0..TRIES).map(|_| exponential(2.0)).sum::<f64>() / TRIES as f64;
+		let norm = (0..TRIES).map(|_| normal(3.0, 1.0)).sum::<f64>() / TRIES as f64;
+		let geo = (0..TRIES).map(|_| geometric(0.25) as f64).sum::<f64>() / TRIES as f64;
+
+		println!("Means: {exp}, {norm}, {geo}");
+		assert!((exp - 0.5).abs() < 0.01);
+		assert!((norm - 3.0).abs() < 0.02);
+		// `(1 - p) / p`
+		assert!((geo - 3.0).abs() < 0.05);
+	}
+
+	#[test]
+	fn range_f64_bounds() {
+		for _ in 0..10_000 {
+			assert!((2.0..3.0).contains(&range_f64(2.0, 3.0)));
+		}
+
+		// A range one ULP wide only has `low` in it
+		let low = 1.0f64;
+		let high = f64::from_bits(low.to_bits() + 1);
+		assert_eq!(range_f64(low, high), low);
+	}
+
+	#[test]
+	#[should_panic]
+	fn range_f64_empty() {
+		range_f64(1.0, 1.0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn range_f64_too_wide() {
+		range_f64(f64::MIN, f64::MAX);
+	}
+
+	#[test]
+	fn normal_vec_moments() {
+		const LEN: usize = 100_001;
+
+		let samples = normal_vec(-2.0, 3.0, LEN);
+		assert_eq!(samples.len(), LEN);
+
+		let mean = samples.iter().sum::<f64>() / LEN as f64;
+		let var = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / LEN as f64;
+
+		println!("Mean: {mean}, variance: {var}");
+		assert!((mean + 2.0).abs() < 0.05);
+		assert!((var - 9.0).abs() < 0.2);
+		assert!(normal_vec(0.0, 1.0, 0).is_empty());
+	}
+
+	#[test]
+	fn inclusive_endpoints() {
+		assert_eq!(unit_closed(0), 0.0);
+		assert_eq!(unit_closed(u64::MAX), 1.0);
+
+		// Both extreme draws land exactly on the endpoints
+		let (low, high) = (0.1, 0.7);
+		assert_eq!(
+			range_closed_from(unit_closed(0), low, high - low, high),
+			low
+		);
+		assert_eq!(
+			range_closed_from(unit_closed(u64::MAX), low, high - low, high),
+			high
+		);
+
+		for _ in 0..10_000 {
+			assert!((0.0..=1.0).contains(&closed01()));
+			assert!((-3.0..=3.0).contains(&range_f64_inclusive(-3.0, 3.0)));
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn inclusive_inverted() {
+		range_f64_inclusive(1.0, 0.0);
+	}
+
+	#[test]
+	fn walks() {
+		let walk = random_walk(10_000, 2.0);
+		assert_eq!(walk.len(), 10_000);
+		assert!((walk[0]).abs() <= 2.0);
+		assert!(walk.windows(2).all(|w| (w[1] - w[0]).abs() <= 2.0));
+
+		assert_eq!(
+			seeded_random_walk(9, 50, 1.0),
+			seeded_random_walk(9, 50, 1.0)
+		);
+		assert_ne!(
+			seeded_random_walk(9, 50, 1.0),
+			seeded_random_walk(8, 50, 1.0)
+		);
+		assert_eq!(random_walk(5, 0.0), [0.0; 5]);
+		assert!(random_walk(0, 1.0).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn walk_negative_step() {
+		random_walk(1, -1.0);
+	}
+
+	#[test]
+	fn around_is_triangular() {
+		const TRIES: usize = 100_000;
+
+		let mut inner = 0;
+
+		for _ in 0..TRIES {
+			let r = around(10.0, 2.0);
+			assert!((8.0..=12.0).contains(&r));
+
+			inner += (9.0..11.0).contains(&r) as usize;
+		}
+
+		// The middle half of the support holds 3/4 of the mass
+		let ratio = inner as f64 / TRIES as f64;
+		println!("Inner ratio: {ratio}");
+		assert!((ratio - 0.75).abs() < 0.01);
+	}
+
+	#[test]
+	fn rounded() {
+		for _ in 0..1_000 {
+			let r = rounded_f64(-10.0, 10.0, 1);
+			assert!((-10.0..=10.0).contains(&r));
+			// Printed with at most one decimal
+			assert!(format!("{r}")
+				.split('.')
+				.nth(1)
+				.is_none_or(|d| d.len() == 1));
+
+			let i = rounded_f64(0.0, 5.0, 0);
+			assert_eq!(i.fract(), 0.0);
+			assert!((0.0..=5.0).contains(&i));
+		}
+
+		// No rounding left to do
+		let r = rounded_f64(0.0, 1e-300, 400);
+		assert!((0.0..1e-300).contains(&r));
+		rounded_f64(0.0, 1.0, u32::MAX);
+	}
+
+	#[test]
+	fn loguniform_decades() {
+		const TRIES: usize = 100_000;
+
+		let mut decades = [0usize; 4];
+
+		for _ in 0..TRIES {
+			let r = loguniform(1e-5, 1e-1);
+			assert!((1e-5..1e-1).contains(&r));
+
+			decades[((r.log10() + 5.0) as usize).min(3)] += 1;
+		}
+
+		println!("Decades: {decades:?}");
+		for d in decades {
+			assert!((d as f64 / TRIES as f64 - 0.25).abs() < 0.01);
+		}
+
+		let r = loguniform(1.0, 1.0 + f64::EPSILON);
+		assert_eq!(r, 1.0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn loguniform_zero_low() {
+		loguniform(0.0, 1.0);
+	}
+
+	#[test]
+	fn zipf_frequencies() {
+		const TRIES: usize = 200_000;
+
+		for exponent in [0.5, 1.0, 2.0] {
+			let dist = ZipfDistribution::new(10, exponent);
+			let mut counts = [0usize; 11];
+
+			for _ in 0..TRIES {
+				counts[dist.sample()] += 1;
+			}
+
+			assert_eq!(counts[0], 0);
+
+			let norm: f64 = (1..=10).map(|k| (k as f64).powf(-exponent)).sum();
+			for (k, &c) in counts.iter().enumerate().skip(1) {
+				let expected = (k as f64).powf(-exponent) / norm;
+				let freq = c as f64 / TRIES as f64;
+
+				assert!(
+					(freq - expected).abs() < 0.005,
+					"exponent {exponent}, k = {k}: {freq} vs {expected}"
+				);
+			}
+		}
+
+		let huge = ZipfDistribution::new(usize::MAX, 1.2);
+		assert!((0..1_000).all(|_| huge.sample() >= 1));
+		assert_eq!(zipf(1, 0.1), 1);
+	}
+
+	#[test]
+	#[should_panic]
+	fn zipf_zero_exponent() {
+		zipf(10, 0.0);
+	}
+
+	#[test]
+	fn inverse_transform_logistic() {
+		const TRIES: usize = 100_000;
+
+		// The logistic quantile is infinite at both ends
+		let logit = |u: f64| (u / (1.0 - u)).ln();
+		let draws: Vec<f64> = (0..TRIES).map(|_| inverse_transform(logit)).collect();
+
+		assert!(draws.iter().all(|x| x.is_finite()));
+
+		let below_one = draws.iter().filter(|&&x| x < 1.0).count() as f64 / TRIES as f64;
+		let expected = 1.0 / (1.0 + (-1.0f64).exp());
+		println!("P(X < 1) = {below_one}, expected {expected}");
+		assert!((below_one - expected).abs() < 0.01);
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = Rng::from_seed(89);
+
+			(
+				rng.closed01(),
+				rng.range_f64_inclusive(-1.0, 1.0),
+				rng.random_walk(10, 0.5),
+				rng.rounded_f64(1.0, 100.0, 2),
+				rng.around(100.0, 20.0),
+				rng.open01(),
+				rng.inverse_transform(|u| (1.0 - u).powf(-1.0 / 3.0)),
+				rng.normal_vec(0.0, 1.0, 5),
+				rng.zipf(1000, 1.1),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	fn markov_runs() {
+		const TRIES: usize = 200_000;
+
+		let draws: Vec<bool> = MarkovBool::new(0.9, 0.7).take(TRIES).collect();
+
+		// Long-run share of `true`s is `0.3 / (0.1 + 0.3)`
+		let share = draws.iter().filter(|&&b| b).count() as f64 / TRIES as f64;
+		println!("Share of trues: {share}");
+		assert!((share - 0.75).abs() < 0.02);
+
+		let (mut stays, mut trues) = (0, 0);
+		for w in draws.windows(2).filter(|w| w[0]) {
+			trues += 1;
+			stays += w[1] as usize;
+		}
+
+		let stay = stays as f64 / trues as f64;
+		println!("P(true | true) = {stay}");
+		assert!((stay - 0.9).abs() < 0.01);
+
+		// Always flipping
+		let flips: Vec<bool> = MarkovBool::new(0.0, 0.0).take(100).collect();
+		assert!(flips.windows(2).all(|w| w[0] != w[1]));
+	}
+
+	#[test]
+	#[should_panic]
+	fn markov_invalid() {
+		MarkovBool::new(1.2, 0.5);
+	}
+}
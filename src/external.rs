@@ -0,0 +1,92 @@
+//! [`Random`] for popular ecosystem types, every crate behind its own feature
+
+use crate::{Global, Random, Source};
+
+/// A random version 4 UUID: 122 random bits with the version and variant bits set
+///
+/// # Example
+/// ```
+/// use hel_random::Random;
+///
+/// let id = uuid::Uuid::random();
+///
+/// assert_eq!(id.get_version_num(), 4);
+/// ```
+#[cfg(feature = "uuid")]
+impl Random for uuid::Uuid {
+	#[inline]
+	fn random() -> Self {
+		Self::random_from(&mut Global)
+	}
+
+	#[inline]
+	fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+		let mut bytes = [0; 16];
+		source.fill_bytes(&mut bytes);
+
+		uuid::Builder::from_random_bytes(bytes).into_uuid()
+	}
+}
+
+/// A random date and time in `[1970-01-01, 2100-01-01)` with nanosecond precision
+///
+/// # Example
+/// ```
+/// use chrono::{Datelike, NaiveDateTime};
+/// use hel_random::Random;
+///
+/// let t = NaiveDateTime::random();
+///
+/// assert!((1970..2100).contains(&t.year()));
+/// ```
+#[cfg(feature = "chrono")]
+impl Random for chrono::NaiveDateTime {
+	#[inline]
+	fn random() -> Self {
+		Self::random_from(&mut Global)
+	}
+
+	fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+		// 2100-01-01T00:00:00Z
+		const END: u64 = 4_102_444_800;
+
+		let secs = source.below(END) as i64;
+		let nanos = source.below(1_000_000_000) as u32;
+
+		chrono::DateTime::from_timestamp(secs, nanos)
+			.expect("timestamp is in range")
+			.naive_utc()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	#[cfg(feature = "uuid")]
+	fn uuid_v4() {
+		for _ in 0..100 {
+			let id = uuid::Uuid::random();
+
+			assert_eq!(id.get_version(), Some(uuid::Version::Random));
+			assert_eq!(id.get_variant(), uuid::Variant::RFC4122);
+		}
+
+		let mut rng = crate::Rng::from_seed(1);
+		let a: uuid::Uuid = rng.generate();
+		let b: uuid::Uuid = crate::Rng::from_seed(1).generate();
+		assert_eq!(a, b);
+	}
+
+	#[test]
+	#[cfg(feature = "chrono")]
+	fn chrono_range() {
+		use chrono::Datelike;
+
+		for _ in 0..1_000 {
+			let t = chrono::NaiveDateTime::random();
+			assert!((1970..2100).contains(&t.year()));
+		}
+	}
+}
@@ -0,0 +1,91 @@
+//! Random email- and domain-like strings for seeding test databases
+//!
+//! The values are only syntactically plausible, every domain starts with `rand-` so they are easy
+//! to tell apart from real data.
+
+use crate::{Global, Source};
+
+// A few common top-level domains to pick from
+const TLDS: [&str; 6] = ["com", "net", "org", "io", "dev", "app"];
+
+/// Will generate a domain like `rand-xyz.com`
+///
+/// # Example
+/// ```
+/// let domain = hel_random::fake::domain();
+///
+/// let (name, tld) = domain.split_once('.').unwrap();
+/// assert!(name.starts_with("rand-"));
+/// assert!(tld.chars().all(|c| c.is_ascii_lowercase()));
+/// ```
+pub fn domain() -> String {
+	Global.domain()
+}
+
+pub(crate) fn domain_from<S: Source>(source: &mut S) -> String {
+	let len = source.range(3, 9) as usize;
+	let name = source.ascii_lowercase_string(len);
+	let tld = TLDS[source.below(TLDS.len() as u64) as usize];
+
+	format!("rand-{name}.{tld}")
+}
+
+/// Will generate an email like `abc123@rand-xyz.com`
+///
+/// The local part is 3 to 8 lowercase letters followed by up to 4 digits, the domain is a [`domain`]
+///
+/// # Example
+/// ```
+/// let email = hel_random::fake::email();
+///
+/// let (local, domain) = email.split_once('@').unwrap();
+/// assert!(local.chars().all(|c| c.is_ascii_alphanumeric()));
+/// assert!(domain.starts_with("rand-"));
+/// ```
+pub fn email() -> String {
+	Global.email()
+}
+
+pub(crate) fn email_from<S: Source>(source: &mut S) -> String {
+	let len = source.range(3, 9) as usize;
+	let name = source.ascii_lowercase_string(len);
+	let len = source.below(5) as usize;
+	let digits = source.ascii_digit_string(len);
+
+	format!("{name}{digits}@{}", domain_from(source))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn emails_are_well_formed() {
+		for _ in 0..1_000 {
+			let email = email();
+			let (local, domain) = email.split_once('@').unwrap();
+
+			assert!((3..=12).contains(&local.len()));
+			assert!(local.starts_with(|c: char| c.is_ascii_lowercase()));
+			assert!(local
+				.chars()
+				.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+
+			let (name, tld) = domain.rsplit_once('.').unwrap();
+			let label = name.strip_prefix("rand-").unwrap();
+
+			assert!((3..=8).contains(&label.len()));
+			assert!(label.chars().all(|c| c.is_ascii_lowercase()));
+			assert!(TLDS.contains(&tld));
+		}
+	}
+	#[test]
+	fn seeded_fakes() {
+		let run = || {
+			let mut rng = crate::Rng::from_seed(34);
+			(rng.domain(), rng.email())
+		};
+
+		assert_eq!(run(), run());
+	}
+}
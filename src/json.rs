@@ -0,0 +1,234 @@
+//! Random JSON-like trees for fuzzing serializers
+
+use std::fmt;
+
+use crate::{Global, Random, Source};
+
+// Most children an array or object gets, keeps the tree from exploding
+const MAX_BREADTH: u64 = 4;
+
+/// A lightweight JSON value, [`Display`](fmt::Display) emits valid JSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+	/// `null`
+	Null,
+	/// `true` or `false`
+	Bool(bool),
+	/// Always finite
+	Number(f64),
+	/// Any UTF-8, escaped when displayed
+	String(String),
+	/// `[...]`
+	Array(Vec<JsonValue>),
+	/// `{...}`, keys may repeat
+	Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+	/// Will return the nesting depth, `0` for scalars
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::json::JsonValue;
+	///
+	/// let v = JsonValue::Array(vec![JsonValue::Array(vec![]), JsonValue::Null]);
+	///
+	/// assert_eq!(v.depth(), 2);
+	/// assert_eq!(JsonValue::Null.depth(), 0);
+	/// ```
+	pub fn depth(&self) -> usize {
+		let children = match self {
+			JsonValue::Array(items) => items.iter().map(JsonValue::depth).max(),
+			JsonValue::Object(entries) => entries.iter().map(|(_, v)| v.depth()).max(),
+			_ => return 0,
+		};
+
+		1 + children.unwrap_or(0)
+	}
+}
+
+impl fmt::Display for JsonValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			JsonValue::Null => f.write_str("null"),
+			JsonValue::Bool(b) => write!(f, "{b}"),
+			// `Display` for `f64` never uses an exponent, so it's a valid JSON number
+			JsonValue::Number(n) => write!(f, "{n}"),
+			JsonValue::String(s) => write_string(f, s),
+			JsonValue::Array(items) => {
+				f.write_str("[")?;
+
+				for (i, item) in items.iter().enumerate() {
+					if i > 0 {
+						f.write_str(",")?;
+					}
+
+					write!(f, "{item}")?;
+				}
+
+				f.write_str("]")
+			}
+			JsonValue::Object(entries) => {
+				f.write_str("{")?;
+
+				for (i, (key, value)) in entries.iter().enumerate() {
+					if i > 0 {
+						f.write_str(",")?;
+					}
+
+					write_string(f, key)?;
+					write!(f, ":{value}")?;
+				}
+
+				f.write_str("}")
+			}
+		}
+	}
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+	f.write_str("\"")?;
+
+	for c in s.chars() {
+		match c {
+			'"' => f.write_str("\\\"")?,
+			'\\' => f.write_str("\\\\")?,
+			c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+			c => write!(f, "{c}")?,
+		}
+	}
+
+	f.write_str("\"")
+}
+
+/// Will generate a random JSON tree nested at most `max_depth` levels deep
+///
+/// Arrays and objects get up to 4 children. Numbers are a mix of integers and fractions,
+/// strings mix plain alphanumerics with any scalar value to exercise escaping.
+///
+/// # Example
+/// ```
+/// use hel_random::json::random_json_value;
+///
+/// let value = random_json_value(3);
+///
+/// assert!(value.depth() <= 3);
+/// println!("{value}");
+/// ```
+pub fn random_json_value(max_depth: usize) -> JsonValue {
+	Global.random_json_value(max_depth)
+}
+
+pub(crate) fn random_json_value_from<S: Source>(source: &mut S, max_depth: usize) -> JsonValue {
+	// Containers only while there's depth left
+	let kinds = if max_depth == 0 { 4 } else { 6 };
+
+	match source.below(kinds) {
+		0 => JsonValue::Null,
+		1 => JsonValue::Bool(bool::random_from(source)),
+		2 => JsonValue::Number(random_number(source)),
+		3 => JsonValue::String(random_string(source)),
+		4 => JsonValue::Array(
+			(0..source.below(MAX_BREADTH + 1))
+				.map(|_| random_json_value_from(source, max_depth - 1))
+				.collect(),
+		),
+		_ => JsonValue::Object(
+			(0..source.below(MAX_BREADTH + 1))
+				.map(|_| {
+					let key = random_string(source);
+					(key, random_json_value_from(source, max_depth - 1))
+				})
+				.collect(),
+		),
+	}
+}
+
+fn random_number<S: Source>(source: &mut S) -> f64 {
+	if bool::random_from(source) {
+		i32::random_from(source) as f64
+	} else {
+		(f64::random_from(source) - 0.5) * 2e6
+	}
+}
+
+fn random_string<S: Source>(source: &mut S) -> String {
+	let len = source.below(9) as usize;
+
+	if bool::random_from(source) {
+		source.alphanumeric_string(len)
+	} else {
+		(0..len).map(|_| char::random_from(source)).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Brackets outside of strings nest properly, strings hold no raw control characters
+	fn is_balanced(text: &str) -> bool {
+		let mut stack = Vec::new();
+		let (mut in_string, mut escaped) = (false, false);
+
+		for c in text.chars() {
+			if in_string {
+				match c {
+					_ if escaped => escaped = false,
+					'\\' => escaped = true,
+					'"' => in_string = false,
+					c if (c as u32) < 0x20 => return false,
+					_ => {}
+				}
+
+				continue;
+			}
+
+			match c {
+				'"' => in_string = true,
+				'[' | '{' => stack.push(c),
+				']' if stack.pop() != Some('[') => return false,
+				'}' if stack.pop() != Some('{') => return false,
+				_ => {}
+			}
+		}
+
+		stack.is_empty() && !in_string
+	}
+
+	#[test]
+	fn depth_is_capped() {
+		for max_depth in 0..5 {
+			for _ in 0..200 {
+				let value = random_json_value(max_depth);
+				assert!(value.depth() <= max_depth);
+
+				assert!(is_balanced(&value.to_string()), "{value}");
+			}
+		}
+	}
+
+	#[test]
+	fn display_escapes() {
+		let value = JsonValue::Object(vec![
+			(
+				"a\"b".into(),
+				JsonValue::Array(vec![JsonValue::Null, JsonValue::Bool(true)]),
+			),
+			("\\\n".into(), JsonValue::Number(-1.5)),
+			("ü".into(), JsonValue::Number(1e21)),
+		]);
+
+		assert_eq!(
+			value.to_string(),
+			r#"{"a\"b":[null,true],"\\\u000a":-1.5,"ü":1000000000000000000000}"#
+		);
+		assert_eq!(JsonValue::Array(vec![]).to_string(), "[]");
+		assert_eq!(JsonValue::Object(vec![]).to_string(), "{}");
+	}
+	#[test]
+	fn seeded_values() {
+		let run = || crate::Rng::from_seed(55).random_json_value(4);
+		assert_eq!(run(), run());
+	}
+}
@@ -1,8 +1,25 @@
 //! A simple pseudo non-cryptographic random number generator.
 //! Using xoshiro256++ under the hood.
+//!
+//! The global state is seeded at startup from heap garbage. To reproduce a run, set
+//! `HEL_RANDOM_SEED` to a [`u64`] and the state will be expanded from it instead.
+//! Setting `HEL_RANDOM_PRINT_SEED` prints the seed in use to stderr, so a flaky run can be replayed.
+//!
+//! The global state is a single unsynchronized static by default. That is as fast as it gets,
+//! but threads drawing at the same time race on it and may see repeated or torn values.
+//! The `shared-global` feature puts it behind a [`Mutex`](std::sync::Mutex) instead, so all threads
+//! share one coherent sequence at the cost of a lock per draw, which gets slow under contention.
+//! For independent per-thread streams use an [`Rng`] per thread.
 #![warn(missing_docs)]
 //
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
+
+use std::{
+	collections::HashSet,
+	ops::{Range, RangeInclusive},
+	ptr::addr_of_mut,
+	time::{Duration, Instant, SystemTime},
+};
 
 const STATE_SIZE: usize = 4;
 
@@ -11,6 +28,74 @@ type StateType = [Target; STATE_SIZE];
 
 static mut STATE: StateType = [0, 0, 0, 0];
 
+// The live state and reseed interval set aside by `enter_test_mode`
+static mut LIVE: Option<(StateType, u64)> = None;
+
+// What `init` seeded `STATE` with, until `take_initial_state` takes it
+static mut INITIAL_STATE: Option<StateType> = None;
+
+const SEED_VAR: &str = "HEL_RANDOM_SEED";
+const PRINT_SEED_VAR: &str = "HEL_RANDOM_PRINT_SEED";
+
+// Every `AUTO_RESEED_INTERVAL`-th global step mixes the time since `RESEED_EPOCH` into `STATE`, 0 is off
+static mut AUTO_RESEED_INTERVAL: u64 = 0;
+static mut STEPS_SINCE_RESEED: u64 = 0;
+static mut RESEED_EPOCH: Option<Instant> = None;
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod collections;
+#[cfg(all(
+	feature = "color",
+	any(target_os = "linux", target_os = "windows", target_os = "macos")
+))]
+pub mod color;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod distributions;
+#[cfg(all(
+	any(feature = "chrono", feature = "uuid"),
+	any(target_os = "linux", target_os = "windows", target_os = "macos")
+))]
+mod external;
+#[cfg(all(
+	feature = "fake",
+	any(target_os = "linux", target_os = "windows", target_os = "macos")
+))]
+pub mod fake;
+#[cfg(all(
+	feature = "json",
+	any(target_os = "linux", target_os = "windows", target_os = "macos")
+))]
+pub mod json;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod net;
+mod rng;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod seq;
+mod source;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod strings;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub mod test_utils;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+mod weighted;
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use collections::*;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use distributions::*;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use net::*;
+pub use rng::{KeyedRng, Rng, RngBuilder, Xoshiro};
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use seq::*;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use source::Global;
+pub use source::{ByteReader, Source};
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use strings::*;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub use weighted::*;
+
 #[used]
 #[cfg_attr(target_os = "linux", link_section = ".init_array")]
 #[cfg_attr(target_os = "macos", link_section = "__DATA,__mod_init_func")]
@@ -55,9 +140,26 @@ static INIT: extern "C" fn() = {
 				*garbage = val
 			}
 
-			STATE = res;
+			dealloc(ptr, layout);
+
+			let print = std::env::var_os(PRINT_SEED_VAR).is_some();
 
-			dealloc(ptr, layout)
+			// To print a reproducible seed, it has to be derived from the garbage first
+			let seed = std::env::var(SEED_VAR)
+				.ok()
+				.and_then(|s| parse_seed(&s))
+				.or_else(|| print.then(|| res.iter().fold(0, |acc, &w| mix64(acc ^ w))));
+
+			if let Some(seed) = seed {
+				if print {
+					eprintln!("{SEED_VAR}={seed}");
+				}
+
+				res = expand_seed(seed);
+			}
+
+			STATE = res;
+			INITIAL_STATE = Some(res);
 		}
 	}
 
@@ -65,268 +167,2587 @@ static INIT: extern "C" fn() = {
 };
 
 #[inline]
-fn xoshiro256pp() {
-	unsafe {
-		let s = STATE[1] << 17;
+fn xoshiro256pp(state: &mut StateType) {
+	let s = state[1] << 17;
 
-		STATE[2] ^= STATE[0];
-		STATE[3] ^= STATE[1];
-		STATE[1] ^= STATE[2];
-		STATE[0] ^= STATE[3];
+	state[2] ^= state[0];
+	state[3] ^= state[1];
+	state[1] ^= state[2];
+	state[0] ^= state[3];
 
-		STATE[2] ^= s;
+	state[2] ^= s;
 
-		STATE[3] = STATE[3].rotate_left(45);
-	}
+	state[3] = state[3].rotate_left(45);
 }
 
-/// A helper trait to generate random values
-#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
-pub trait Random: Sized {
-	/// Will generate a random [`Self`]
-	fn random() -> Self;
+/// The `++` scrambler, the output for the current state which is then advanced
+#[inline(always)]
+fn scramble(state: &StateType) -> Target {
+	state[0]
+		.wrapping_add(state[3])
+		.rotate_left(23)
+		.wrapping_add(state[0])
 }
 
-/// Generic function that returns a random [`T`]
+/// The `**` scrambler, the xoshiro256** output for the current state
+#[inline(always)]
+fn scramble_starstar(state: &StateType) -> Target {
+	state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9)
+}
+
+/// The SplitMix64 finalizer, a fast bijective mixer of 64 bit values
+///
+/// This is what [`Rng::from_seed`] uses to expand a seed, handy for deterministic derivations
+/// like hashing coordinates for procedural generation. `mix64(0) == 0`.
 ///
 /// # Example
 /// ```
-/// use hel_random::generate;
+/// use hel_random::mix64;
 ///
-/// let a: u64 = generate();
-/// let b: u32 = generate();
-/// let c = generate::<i128>();
+/// let tile = mix64(12 << 32 | 34);
 ///
-/// println!("a = {a}");
-/// println!("b = {b}");
-/// println!("c = {c}");
+/// assert_eq!(tile, mix64(12 << 32 | 34));
+/// assert_ne!(mix64(1), mix64(2));
 /// ```
-#[inline(always)]
-#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
-pub fn generate<T: Random>() -> T {
-	T::random()
+#[inline]
+pub const fn mix64(mut x: u64) -> u64 {
+	x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+	x ^ (x >> 31)
 }
 
-macro_rules! make {
-	($type: ident, $code: block) => {
-		#[doc = concat!("Will generate a random ", stringify!($type))]
-		///
-		/// # Example
-		/// ```
-		#[doc = concat!("let a: ", stringify!($type), " = hel_random::", stringify!($type), "();")]
-		#[doc = concat!("let b: ", stringify!($type), " = hel_random::", stringify!($type), "();")]
-		/// // Examples generated by macro, so we check if type is `bool` or `i8` or `u8` to avoid collisions
-		#[doc = concat!("if std::mem::size_of::<", stringify!($type), ">() > 1 {")]
-		///     assert!(a != b);
-		/// }
-		/// ```
-		#[inline]
-		#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
-		pub fn $type() -> $type {
-			$code
-		}
+#[inline]
+fn parse_seed(s: &str) -> Option<u64> {
+	s.trim().parse().ok()
+}
 
-		impl Random for $type {
-			#[doc = concat!("Will generate a random ", stringify!($type))]
-			///
-			/// # Example
-			/// ```
-			/// use hel_random::Random;
-			///
-			#[doc = concat!("let r = ", stringify!($type), "::random();")]
-			/// println!("r = {r}");
-			/// ```
-			#[inline(always)]
-			fn random() -> Self {
-				$type()
-			}
-		}
-	};
+#[inline]
+fn splitmix64(x: &mut Target, gamma: Target) -> Target {
+	*x = x.wrapping_add(gamma);
 
-	($type: ident) => {
-		make!($type, { u64() as $type });
-	};
+	mix64(*x)
 }
 
-make!(u128, {
-	xoshiro256pp();
+// The increment of the reference SplitMix64
+const GOLDEN_GAMMA: Target = 0x9e3779b97f4a7c15;
 
-	unsafe {
-		STATE[0].wrapping_add(STATE[2]) as u128 | (((STATE[1]).wrapping_add(STATE[3]) as u128) << 64)
-	}
-});
-make!(i128, { u128() as i128 });
+/// Maps a word onto the midpoints of a `2^-52` grid, so the result is in `(0, 1)` and never `0` or `1`
+#[inline]
+fn unit_open(x: u64) -> f64 {
+	((x >> 12) as f64 + 0.5) * (1.0 / (1u64 << 52) as f64)
+}
 
-make!(u64, {
-	xoshiro256pp();
-	unsafe {
-		STATE[0]
-			.wrapping_add(STATE[3])
-			.rotate_left(23)
-			.wrapping_add(STATE[0])
-	}
-});
-make!(i64);
-make!(u32);
-make!(i32);
-make!(u16);
-make!(i16);
-make!(u8);
-make!(i8);
+/// A pair of independent standard normal samples from two uniforms (Box–Muller)
+///
+/// Both uniforms come from [`unit_open`], so `ln` is always finite
+#[inline]
+fn standard_normal_pair_from<S: Source + ?Sized>(source: &mut S) -> (f64, f64) {
+	let u1 = unit_open(source.next_u64());
+	let u2 = unit_open(source.next_u64());
 
-make!(bool, {
-	unsafe {
-		// runtime check is necessary to avoid infinite loop
-		if STATE[0] == 0 {
-			return false;
-		}
+	let r = (-2.0 * u1.ln()).sqrt();
+	let (sin, cos) = (std::f64::consts::TAU * u2).sin_cos();
 
-		loop {
-			xoshiro256pp();
+	(r * cos, r * sin)
+}
 
-			let a = (STATE[0] & 1) == 1;
-			let b = (STATE[2] & 1) == 1;
+/// Expands a single word into a full state with SplitMix64
+#[inline]
+fn expand_seed(seed: u64) -> StateType {
+	expand_seed_with(seed, GOLDEN_GAMMA)
+}
 
-			if a != b {
-				return a;
-			}
-		}
-	}
-});
+/// Same as [`expand_seed`] with a custom (odd) SplitMix64 increment
+#[inline]
+fn expand_seed_with(mut seed: u64, gamma: Target) -> StateType {
+	[
+		splitmix64(&mut seed, gamma),
+		splitmix64(&mut seed, gamma),
+		splitmix64(&mut seed, gamma),
+		splitmix64(&mut seed, gamma),
+	]
+}
 
-#[cfg(test)]
-mod tests {
-	extern crate test;
+/// The SplitMix64 increment of stream `stream_id`, stream `0` gets the reference one
+#[inline]
+fn stream_gamma(stream_id: u64) -> Target {
+	let gamma = (GOLDEN_GAMMA ^ mix64(stream_id)) | 1;
+
+	// Like `SplittableRandom`, increments with too few bit flips mix poorly
+	if (gamma ^ (gamma >> 1)).count_ones() < 24 {
+		gamma ^ 0xaaaa_aaaa_aaaa_aaaa
+	} else {
+		gamma
+	}
+}
 
-	use std::collections::HashSet;
+// Guards `STATE` and the reseed counters with the `shared-global` feature
+#[cfg(feature = "shared-global")]
+static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
-	use test::Bencher;
+/// Runs `f` on the global state, the only way the generator functions touch it
+///
+/// With the `shared-global` feature this holds [`LOCK`], otherwise it's a plain unsynchronized access
+#[inline(always)]
+fn with_state<R>(f: impl FnOnce(&mut StateType) -> R) -> R {
+	#[cfg(feature = "shared-global")]
+	let _guard = LOCK
+		.lock()
+		.unwrap_or_else(std::sync::PoisonError::into_inner);
 
-	use super::*;
+	unsafe { f(&mut *addr_of_mut!(STATE)) }
+}
 
-	#[test]
-	fn flip_a_coin_fairness() {
-		unsafe {
-			const TRIES: i64 = 1_000_000;
+/// Advances the global state, must be called from [`with_state`]
+#[inline]
+fn step(state: &mut StateType) {
+	// Another constructor may run before ours and ask for a number
+	lazy_seed(state);
 
-			let mut balance = 0;
+	advance(state);
+}
 
-			bool();
+/// Same as [`step`] for a state known to be seeded
+#[inline(always)]
+fn advance(state: &mut StateType) {
+	xoshiro256pp(state);
 
-			println!("State: {:?}", STATE);
-			println!(
-				"Population: {}",
-				STATE.iter().fold(0, |acc, s| acc + s.count_ones())
+	unsafe {
+		if AUTO_RESEED_INTERVAL != 0 {
+			auto_reseed(
+				state,
+				&mut *addr_of_mut!(STEPS_SINCE_RESEED),
+				AUTO_RESEED_INTERVAL,
+				&mut *addr_of_mut!(RESEED_EPOCH),
 			);
-
-			for _ in 0..TRIES {
-				balance += if bool() { 1 } else { -1 };
-			}
-
-			println!("Fairness: {balance}");
-			assert!(balance < (TRIES / 100));
 		}
 	}
+}
 
-	macro_rules! make_test {
-		($test_name: ident, $bench_name: ident, $subject: ident) => {
-			#[bench]
-			fn $test_name(b: &mut Bencher) {
-				let _w = b.iter($subject);
-
-				assert!(u64() > 0);
-			}
+/// Seeds `state` from the system time if it is still all zeros, a seeded state never is
+#[inline(always)]
+fn lazy_seed(state: &mut StateType) {
+	// An OR of the words compiles to much less than an array comparison on this hot path
+	if state.iter().fold(0, |acc, w| acc | w) == 0 {
+		fallback_seed(state);
+	}
+}
 
-			#[bench]
-			#[ignore = "generating 1_000_000 result. Use 'cargo bench -- --ignored'"]
-			fn $bench_name(b: &mut Bencher) {
-				let _w = b.iter(|| {
-					let mut res = $subject();
+#[cold]
+#[inline(never)]
+fn fallback_seed(state: &mut StateType) {
+	// The address of a local adds a bit of ASLR entropy on top of the time
+	let local = 0u8;
+	let addr = std::hint::black_box(&local as *const u8 as Target);
 
-					for _ in 0..1_000_000 {
-						res = $subject();
-					}
+	*state = expand_seed(time_nanos() ^ addr.rotate_left(32));
+}
 
-					res
-				});
+/// Counts a step, reseeding once `interval` of them have passed
+#[inline(always)]
+fn auto_reseed(state: &mut StateType, steps: &mut u64, interval: u64, epoch: &mut Option<Instant>) {
+	*steps += 1;
 
-				assert!(u64() > 0);
-			}
-		};
+	if *steps >= interval {
+		*steps = 0;
+		reseed(state, epoch);
 	}
+}
 
-	make_test!(test_u128, bench_u128, u128);
-	make_test!(test_i128, bench_i128, i128);
-	make_test!(test_u64, bench_u64, u64);
-	make_test!(test_i64, bench_i64, i64);
-	make_test!(test_u32, bench_u32, u32);
-	make_test!(test_i32, bench_i32, i32);
-	make_test!(test_u16, bench_u16, u16);
-	make_test!(test_i16, bench_i16, i16);
-	make_test!(test_u8, bench_u8, u8);
-	make_test!(test_i8, bench_i8, i8);
-	make_test!(test_bool, bench_bool, bool);
-
-	macro_rules! make_ignored {
-		($test_name: ident, $fn_name: ident) => {
-			#[test]
-			#[ignore = r#"to see output use "cargo t -- --ignored""#]
-			fn $test_name() {
-				$fn_name();
+#[cold]
+#[inline(never)]
+fn reseed(state: &mut StateType, epoch: &mut Option<Instant>) {
+	let nanos = epoch.get_or_insert_with(Instant::now).elapsed().as_nanos() as Target;
 
-				unsafe {
-					println!("{:?}", STATE);
-				}
+	mix_in(state, nanos);
+}
 
-				for _ in 0..100 {
-					println!("{}", $fn_name());
-				}
+/// XORs `nanos` into the first word of `state`, keeping it a valid nonzero state
+#[inline]
+fn mix_in(state: &mut StateType, nanos: Target) {
+	state[0] ^= nanos.wrapping_mul(0x9e3779b97f4a7c15);
 
-				assert!(false);
-			}
-		};
+	// Only when words 1 to 3 are zero and the mix hit `state[0]` exactly, but then it would be stuck
+	if *state == [0; STATE_SIZE] {
+		*state = expand_seed(nanos);
 	}
+}
 
-	make_ignored!(output_u128, u128);
-	make_ignored!(output_i128, i128);
-	make_ignored!(output_u64, u64);
-	make_ignored!(output_i64, i64);
-	make_ignored!(output_u32, u32);
-	make_ignored!(output_i32, i32);
-	make_ignored!(output_u16, u16);
-	make_ignored!(output_i16, i16);
-	make_ignored!(output_u8, u8);
-	make_ignored!(output_i8, i8);
-	make_ignored!(output_bool, bool);
-
-	#[test]
-	fn multithreaded() {
-		const THREADS: usize = 1024;
-		let mut threads = Vec::new();
+#[inline]
+fn time_nanos() -> Target {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map_or(0, |d| d.as_nanos() as Target)
+}
 
-		for _ in 0..THREADS {
-			threads.push(std::thread::spawn(|| {
-				let mut res = u64();
+/// Will mix a bit of timing entropy into the global state every `n`-th step, `0` turns it off (default)
+///
+/// Meant for very long running processes, enabling it breaks reproducibility of the global stream
+///
+/// # Example
+/// ```
+/// use hel_random::{set_auto_reseed_interval, u64};
+///
+/// set_auto_reseed_interval(1 << 20);
+/// println!("r = {}", u64());
+/// set_auto_reseed_interval(0);
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn set_auto_reseed_interval(n: u64) {
+	with_state(|_| unsafe {
+		AUTO_RESEED_INTERVAL = n;
+		STEPS_SINCE_RESEED = 0;
+		RESEED_EPOCH = Some(Instant::now());
+	});
+}
 
-				for _ in 0..1_000 {
-					res = u64();
-				}
+/// Will return `true` once the global state has been seeded (it is nonzero)
+///
+/// Seeding runs in a linker section constructor, which some exotic toolchains drop,
+/// see [`ensure_initialized`]
+///
+/// # Example
+/// ```
+/// assert!(hel_random::is_initialized());
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn is_initialized() -> bool {
+	with_state(|state| *state != [0; STATE_SIZE])
+}
 
-				res
-			}))
-		}
+/// Will reseed the global generator from `seed`, expanded with SplitMix64
+///
+/// Same as starting the process with `HEL_RANDOM_SEED` set, and the global stream matches
+/// [`Rng::from_seed`] with the same seed from then on. Useful to get comparable benchmark numbers.
+///
+/// # Example
+/// ```
+/// use hel_random::{seed, u64, Rng};
+///
+/// seed(0);
+///
+/// assert_eq!(u64(), Rng::from_seed(0).next_u64());
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn seed(seed: u64) {
+	with_state(|state| *state = expand_seed(seed));
+}
 
-		let mut res: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
-		res.sort();
+/// Will return the state the global generator was seeded with at startup, only the first call gets it
+///
+/// Log it to reproduce a run later with [`set_state`]. Returns [`None`] on later calls,
+/// and if the startup constructor never ran (see [`ensure_initialized`]).
+///
+/// # Example
+/// ```
+/// use hel_random::{set_state, take_initial_state, u64};
+///
+/// let initial = take_initial_state().unwrap();
+/// assert!(take_initial_state().is_none());
+///
+/// set_state(initial);
+/// let a = u64();
+///
+/// set_state(initial);
+/// assert_eq!(u64(), a);
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn take_initial_state() -> Option<[u64; 4]> {
+	with_state(|_| unsafe { (*addr_of_mut!(INITIAL_STATE)).take() })
+}
+
+/// Will overwrite the global state, e.g. with one from [`take_initial_state`] or [`dump_state`]
+///
+/// # Panics
+/// If `state` is all zeros, xoshiro256++ would only ever return `0`
+///
+/// # Example
+/// ```
+/// use hel_random::{dump_state, set_state};
+///
+/// set_state([1, 2, 3, 4]);
+///
+/// assert_eq!(
+///     dump_state(),
+///     "0000000000000001 0000000000000002 0000000000000003 0000000000000004"
+/// );
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn set_state(state: [u64; 4]) {
+	assert!(state != [0; STATE_SIZE], "the state can't be all zeros");
+
+	with_state(|current| *current = state);
+}
+
+/// Will switch the global generator to a deterministic stream seeded from `seed`, until [`exit_test_mode`]
+///
+/// The live state is set aside and picks up where it left off afterwards. Entering again while
+/// already in test mode just reseeds. Auto reseeding (see [`set_auto_reseed_interval`]) is paused meanwhile.
+///
+/// This is a process-wide toggle, not scoped to a thread: every thread draws from the same seeded stream,
+/// so the sequence a thread sees is only reproducible if nothing else draws concurrently.
+/// [`Rng`] instances are unaffected.
+///
+/// # Example
+/// ```
+/// use hel_random::{enter_test_mode, exit_test_mode, u64};
+///
+/// enter_test_mode(42);
+/// let a = [u64(), u64()];
+///
+/// enter_test_mode(42);
+/// assert_eq!([u64(), u64()], a);
+///
+/// exit_test_mode();
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn enter_test_mode(seed: u64) {
+	with_state(|state| unsafe {
+		let live = &mut *addr_of_mut!(LIVE);
+
+		if live.is_none() {
+			*live = Some((*state, AUTO_RESEED_INTERVAL));
+		}
+
+		AUTO_RESEED_INTERVAL = 0;
+		*state = expand_seed(seed);
+	});
+}
+
+/// Will switch the global generator back to the live stream, does nothing outside of test mode
+///
+/// # Example
+/// ```
+/// use hel_random::{enter_test_mode, exit_test_mode, u64};
+///
+/// enter_test_mode(7);
+/// let seeded = u64();
+/// exit_test_mode();
+///
+/// println!("seeded = {seeded}, live = {}", u64());
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn exit_test_mode() {
+	with_state(|state| unsafe {
+		if let Some((live, interval)) = (*addr_of_mut!(LIVE)).take() {
+			*state = live;
+			AUTO_RESEED_INTERVAL = interval;
+		}
+	});
+}
+
+/// Will format the global state words as hex, the way the xoshiro reference code prints them
+///
+/// Four zero padded 16 digit words separated by spaces, `s[0]` first.
+/// Handy for bug reports and for comparing against reference vectors.
+///
+/// # Example
+/// ```
+/// let dump = hel_random::dump_state();
+///
+/// assert_eq!(dump.len(), 4 * 16 + 3);
+/// println!("{dump}");
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn dump_state() -> String {
+	format_state(&with_state(|state| *state))
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn format_state(state: &StateType) -> String {
+	state
+		.iter()
+		.map(|w| format!("{w:016x}"))
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Will seed the global state from the system time if the startup constructor never ran
+///
+/// An unseeded xoshiro256++ only ever returns `0`. Every generation function already does this lazily,
+/// so calling it by hand is only needed before reading [`dump_state`] or [`is_initialized`].
+///
+/// # Example
+/// ```
+/// use hel_random::{ensure_initialized, is_initialized};
+///
+/// ensure_initialized();
+/// assert!(is_initialized());
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn ensure_initialized() {
+	with_state(lazy_seed);
+}
+
+/// A helper trait to generate random values
+///
+/// Implemented for all primitive integers, [`bool`], [`char`] (any scalar value), floats (uniform in `[0, 1)`) and for the wrappers
+/// [`Reverse<T>`](std::cmp::Reverse), [`Cell<T>`](std::cell::Cell) and [`RefCell<T>`](std::cell::RefCell) where `T: Random`.
+/// The [`NonZero`](std::num) integers are uniform over all their nonzero values.
+/// The [`std::net`] addresses are covered as well, [`IpAddr`](std::net::IpAddr) and [`SocketAddr`](std::net::SocketAddr)
+/// pick the family with a fair coin. With the `uuid` and `chrono` features also for `uuid::Uuid` and `chrono::NaiveDateTime`
+///
+/// # Example
+/// ```
+/// use std::{cell::Cell, cmp::Reverse};
+///
+/// use hel_random::Random;
+///
+/// let a = Reverse::<u32>::random();
+/// let b = Cell::<u8>::random();
+///
+/// println!("a = {}, b = {}", a.0, b.get());
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub trait Random: Sized {
+	/// Will generate a random [`Self`]
+	fn random() -> Self;
+
+	/// Will generate a random [`Self`] from `source`
+	///
+	/// The default implementation ignores `source` and falls back to [`Random::random`],
+	/// implement it to make your type reproducible with seeded sources
+	#[inline(always)]
+	fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+		let _ = source;
+		Self::random()
+	}
+}
+
+/// Generic function that returns a random [`T`]
+///
+/// # Example
+/// ```
+/// use hel_random::generate;
+///
+/// let a: u64 = generate();
+/// let b: u32 = generate();
+/// let c = generate::<i128>();
+///
+/// println!("a = {a}");
+/// println!("b = {b}");
+/// println!("c = {c}");
+/// ```
+#[inline(always)]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn generate<T: Random>() -> T {
+	T::random()
+}
+
+/// Will return `default` with probability `chance_none`, and a fresh `T::random()` otherwise
+///
+/// Handy for mostly random data with the occasional sentinel value
+///
+/// # Panics
+/// If `chance_none` is not in `[0, 1]`
+///
+/// # Example
+/// ```
+/// use hel_random::random_or;
+///
+/// // A zero about every tenth value
+/// let ids: Vec<u32> = (0..100).map(|_| random_or(0.1, 0)).collect();
+///
+/// assert_eq!(random_or(1.0, 7u8), 7);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn random_or<T: Random>(chance_none: f64, default: T) -> T {
+	Global.random_or(chance_none, default)
+}
+
+/// Will run `f` with probability `p` and return its result, otherwise `f` isn't called and it's `None`
+///
+/// For randomized side effects, e.g. injecting faults in chaos tests
+///
+/// # Panics
+/// If `p` is not in `[0, 1]`
+///
+/// # Example
+/// ```
+/// use hel_random::with_probability;
+///
+/// let mut dropped = 0;
+///
+/// for _ in 0..100 {
+///     with_probability(0.05, || dropped += 1);
+/// }
+///
+/// assert_eq!(with_probability(1.0, || 7), Some(7));
+/// assert_eq!(with_probability(0.0, || 7), None);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn with_probability<R, F: FnOnce() -> R>(p: f64, f: F) -> Option<R> {
+	Global.with_probability(p, f)
+}
+
+/// Will draw a `T::random()` and clamp it into `[min, max]`
+///
+/// **This is not uniform over `[min, max]`.** Every draw outside of the bounds lands on `min` or `max`,
+/// so for a narrow range nearly all of the results are one of the two endpoints.
+/// Meant for saturation testing and quick bounding in generic code, use [`range`] for uniform values.
+///
+/// # Panics
+/// If `min > max`
+///
+/// # Example
+/// ```
+/// use hel_random::clamped;
+///
+/// let level: u8 = clamped(10, 20);
+///
+/// assert!((10..=20).contains(&level));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn clamped<T: Random + Ord>(min: T, max: T) -> T {
+	Global.clamped(min, max)
+}
+
+/// A C-like enum (or any type) with a finite number of values that can be picked by index
+///
+/// A lightweight stand-in for a derive, see [`random_variant`]
+///
+/// # Example
+/// ```
+/// use hel_random::{random_variant, RandomVariant};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Suit {
+///     Clubs,
+///     Diamonds,
+///     Hearts,
+///     Spades,
+/// }
+///
+/// impl RandomVariant for Suit {
+///     const COUNT: usize = 4;
+///
+///     fn from_index(i: usize) -> Self {
+///         match i {
+///             0 => Suit::Clubs,
+///             1 => Suit::Diamonds,
+///             2 => Suit::Hearts,
+///             _ => Suit::Spades,
+///         }
+///     }
+/// }
+///
+/// let suit: Suit = random_variant();
+/// println!("suit = {suit:?}");
+/// ```
+pub trait RandomVariant: Sized {
+	/// The number of values, [`RandomVariant::from_index`] is called with indices below it
+	const COUNT: usize;
+
+	/// Will return the value for index `i` in `0..COUNT`
+	fn from_index(i: usize) -> Self;
+}
+
+/// Will return a uniformly chosen value of `T`, see [`RandomVariant`]
+///
+/// # Panics
+/// If `T::COUNT == 0`
+///
+/// # Example
+/// ```
+/// use hel_random::{random_variant, RandomVariant};
+///
+/// #[derive(Clone, Copy)]
+/// enum Coin {
+///     Heads,
+///     Tails,
+/// }
+///
+/// impl RandomVariant for Coin {
+///     const COUNT: usize = 2;
+///
+///     fn from_index(i: usize) -> Self {
+///         if i == 0 { Coin::Heads } else { Coin::Tails }
+///     }
+/// }
+///
+/// let _coin: Coin = random_variant();
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn random_variant<T: RandomVariant>() -> T {
+	Global.random_variant()
+}
+
+macro_rules! make {
+	($type: ident, $code: block, |$source: ident| $from: block) => {
+		#[doc = concat!("Will generate a random ", stringify!($type))]
+		///
+		/// # Example
+		/// ```
+		#[doc = concat!("let a: ", stringify!($type), " = hel_random::", stringify!($type), "();")]
+		#[doc = concat!("let b: ", stringify!($type), " = hel_random::", stringify!($type), "();")]
+		/// // Examples generated by macro, so we check if type is `bool` or `i8` or `u8` to avoid collisions
+		#[doc = concat!("if std::mem::size_of::<", stringify!($type), ">() > 1 {")]
+		///     assert!(a != b);
+		/// }
+		/// ```
+		#[inline]
+		#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+		pub fn $type() -> $type {
+			$code
+		}
+
+		impl Random for $type {
+			#[doc = concat!("Will generate a random ", stringify!($type))]
+			///
+			/// # Example
+			/// ```
+			/// use hel_random::Random;
+			///
+			#[doc = concat!("let r = ", stringify!($type), "::random();")]
+			/// println!("r = {r}");
+			/// ```
+			#[inline(always)]
+			fn random() -> Self {
+				$type()
+			}
+
+			#[inline(always)]
+			fn random_from<S: Source + ?Sized>($source: &mut S) -> Self {
+				$from
+			}
+		}
+	};
+
+	($type: ident, |$source: ident| $from: block) => {
+		make!(
+			$type,
+			{ <$type as Random>::random_from(&mut Global) },
+			|$source| $from
+		);
+	};
+
+	($type: ident) => {
+		make!($type, { u64() as $type }, |source| {
+			source.next_u64() as $type
+		});
+	};
+}
+
+// Two scrambled outputs, low half first
+make!(u128, |source| {
+	source.next_u64() as u128 | ((source.next_u64() as u128) << 64)
+});
+make!(i128, { u128() as i128 }, |source| {
+	u128::random_from(source) as i128
+});
+
+make!(
+	u64,
+	{
+		#[cfg(feature = "test-hooks")]
+		if let Some(value) = test_utils::take_next_value() {
+			return value;
+		}
+
+		with_state(|state| {
+			lazy_seed(state);
+
+			// Output first, then advance, like the reference implementation
+			let res = scramble(state);
+			advance(state);
+
+			res
+		})
+	},
+	|source| { source.next_u64() }
+);
+make!(i64);
+make!(u32);
+make!(i32);
+make!(u16);
+make!(i16);
+make!(u8);
+make!(i8);
+
+// Floats are uniform in `[0, 1)` using all the mantissa bits
+make!(f64, |source| {
+	(source.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+});
+make!(f32, |source| {
+	(source.next_u64() >> 40) as f32 * (1.0 / (1u32 << 24) as f32)
+});
+
+// Uniform over all scalar values, surrogates are skipped
+make!(char, |source| {
+	const SURROGATES: u32 = 0xDFFF - 0xD800 + 1;
+
+	let n = source.below((char::MAX as u32 + 1 - SURROGATES) as u64) as u32;
+	let n = if n < 0xD800 { n } else { n + SURROGATES };
+
+	unsafe { char::from_u32_unchecked(n) }
+});
+
+// The top bit of a single draw, constant time
+make!(bool, |source| { source.next_u64() >> 63 == 1 });
+
+/// Will generate a random [`bool`] by running a von Neumann extractor over the raw state bits
+///
+/// Unlike [`bool()`] this loops until two state bits differ, so its latency is unbounded,
+/// use it only when the stronger decorrelation property is specifically needed
+///
+/// # Example
+/// ```
+/// let a = hel_random::bool_decorrelated();
+/// println!("a = {a}");
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn bool_decorrelated() -> bool {
+	with_state(|state| {
+		// `step` seeds lazily, so the state can't be stuck at zero
+		loop {
+			step(state);
+
+			let a = (state[0] & 1) == 1;
+			let b = (state[2] & 1) == 1;
+
+			if a != b {
+				return a;
+			}
+		}
+	})
+}
+
+#[inline]
+fn below_u128_from<S: Source + ?Sized>(source: &mut S, n: u128) -> u128 {
+	debug_assert!(n > 0);
+
+	if let Ok(n) = u64::try_from(n) {
+		return source.below(n) as u128;
+	}
+
+	// Lemire's method with the rejected zone at the top of the low half instead of the bottom,
+	// exactly as unbiased, but neither an all-zero nor an all-one draw is ever rejected
+	loop {
+		let (high, low) = widening_mul_u128(u128::random_from(source), n);
+
+		if low <= u128::MAX - n {
+			return high;
+		}
+
+		// `2^128 mod n`, the size of the zone
+		let threshold = n.wrapping_neg() % n;
+
+		if low <= u128::MAX - threshold {
+			return high;
+		}
+	}
+}
+
+/// Full 256 bit product of `a` and `b` as `(high, low)` halves
+#[inline]
+fn widening_mul_u128(a: u128, b: u128) -> (u128, u128) {
+	const LOW: u128 = u64::MAX as u128;
+
+	let (a_high, a_low) = (a >> 64, a & LOW);
+	let (b_high, b_low) = (b >> 64, b & LOW);
+
+	let low_low = a_low * b_low;
+	let low_high = a_low * b_high;
+	let high_low = a_high * b_low;
+	let high_high = a_high * b_high;
+
+	// Three values below 2^64 each, no overflow
+	let mid = (low_low >> 64) + (low_high & LOW) + (high_low & LOW);
+
+	let low = (mid << 64) | (low_low & LOW);
+	let high = high_high + (low_high >> 64) + (high_low >> 64) + (mid >> 64);
+
+	(high, low)
+}
+
+/// Uniform [`Duration`] in `[0, max]` with nanosecond resolution
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn duration_up_to<S: Source>(source: &mut S, max: Duration) -> Duration {
+	duration_from_nanos(below_u128_from(source, max.as_nanos() + 1))
+}
+
+/// Inverse of [`Duration::as_nanos`], `nanos` must fit in a [`Duration`]
+#[inline]
+fn duration_from_nanos(nanos: u128) -> Duration {
+	const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+	Duration::new(
+		(nanos / NANOS_PER_SEC) as u64,
+		(nanos % NANOS_PER_SEC) as u32,
+	)
+}
+
+/// Will generate a backoff delay using "full jitter": uniform in `[0, min(cap, base * 2^attempt)]`
+///
+/// If `base * 2^attempt` overflows, the upper bound saturates at `cap`
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use hel_random::full_jitter;
+///
+/// let base = Duration::from_millis(100);
+/// let cap = Duration::from_secs(10);
+///
+/// for attempt in 0..10 {
+///     let delay = full_jitter(base, cap, attempt);
+///     assert!(delay <= cap);
+/// }
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn full_jitter(base: Duration, cap: Duration, attempt: u32) -> Duration {
+	Global.full_jitter(base, cap, attempt)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn full_jitter_from<S: Source>(
+	source: &mut S,
+	base: Duration,
+	cap: Duration,
+	attempt: u32,
+) -> Duration {
+	let cap_nanos = cap.as_nanos();
+
+	// Only shifting out a set bit overflows, a zero base never does
+	let ceil = match base.as_nanos() {
+		0 => 0,
+		base if attempt < base.leading_zeros() => base << attempt,
+		_ => cap_nanos,
+	};
+
+	duration_up_to(source, duration_from_nanos(ceil.min(cap_nanos)))
+}
+
+/// Will generate a [`Duration`] of whole milliseconds, uniform in `[0, max_ms]`
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use hel_random::duration_millis;
+///
+/// let timeout = duration_millis(500);
+///
+/// assert!(timeout <= Duration::from_millis(500));
+/// assert_eq!(timeout.subsec_nanos() % 1_000_000, 0);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn duration_millis(max_ms: u64) -> Duration {
+	Global.duration_millis(max_ms)
+}
+
+/// Will generate a [`Duration`] of whole seconds, uniform in `[0, max_secs]`
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use hel_random::duration_secs;
+///
+/// let uptime = duration_secs(3600);
+///
+/// assert!(uptime <= Duration::from_secs(3600));
+/// assert_eq!(uptime.subsec_nanos(), 0);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn duration_secs(max_secs: u64) -> Duration {
+	Global.duration_secs(max_secs)
+}
+
+/// Will generate `count` timestamps, the first one right after `start`, strictly increasing as long
+/// as they fit in a [`SystemTime`]
+///
+/// Every timestamp is the previous one (or `start`) plus a uniform gap in `(0, max_gap]`,
+/// with nanosecond resolution. Meant for fabricating ordered event logs.
+/// After a step that would overflow [`SystemTime`] the sequence stops increasing, the remaining
+/// timestamps all repeat the last one reached, which can be short of the largest [`SystemTime`].
+///
+/// # Panics
+/// If `max_gap` is zero
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use hel_random::monotonic_times;
+///
+/// let start = SystemTime::UNIX_EPOCH;
+/// let events = monotonic_times(start, Duration::from_secs(5), 100);
+///
+/// assert_eq!(events.len(), 100);
+/// assert!(events[0] > start);
+/// assert!(events.windows(2).all(|w| w[0] < w[1]));
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn monotonic_times(start: SystemTime, max_gap: Duration, count: usize) -> Vec<SystemTime> {
+	Global.monotonic_times(start, max_gap, count)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn monotonic_times_from<S: Source>(
+	source: &mut S,
+	start: SystemTime,
+	max_gap: Duration,
+	count: usize,
+) -> Vec<SystemTime> {
+	assert!(!max_gap.is_zero(), "max_gap must be positive");
+
+	let min_gap = Duration::from_nanos(1);
+	let mut now = start;
+	let mut overflowed = false;
+
+	(0..count)
+		.map(|_| {
+			if !overflowed {
+				let gap = min_gap + duration_up_to(source, max_gap - min_gap);
+
+				match now.checked_add(gap) {
+					Some(next) => now = next,
+					None => overflowed = true,
+				}
+			}
+
+			now
+		})
+		.collect()
+}
+
+/// Why a pair of bounds can't be drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+	/// `low == high`, there is nothing in `[low, high)`
+	Empty {
+		/// The bound given for both ends
+		bound: u64,
+	},
+	/// `low > high`
+	Inverted {
+		/// The lower bound given
+		low: u64,
+		/// The upper bound given
+		high: u64,
+	},
+}
+
+impl std::fmt::Display for RangeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Empty { bound } => write!(f, "range [{bound}, {bound}) is empty"),
+			Self::Inverted { low, high } => write!(f, "range [{low}, {high}) is inverted"),
+		}
+	}
+}
+
+impl std::error::Error for RangeError {}
+
+/// Will generate a uniform [`u64`] in `[low, high)`
+///
+/// # Panics
+/// If `low >= high`, see [`try_range`] for a non-panicking version
+///
+/// # Example
+/// ```
+/// use hel_random::range;
+///
+/// let r = range(10, 20);
+///
+/// assert!((10..20).contains(&r));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn range(low: u64, high: u64) -> u64 {
+	Global.range(low, high)
+}
+
+/// Will generate a uniform [`u64`] in `[low, high)`, or a [`RangeError`] if the bounds are invalid
+///
+/// Meant for bounds coming from untrusted input
+///
+/// # Example
+/// ```
+/// use hel_random::{try_range, RangeError};
+///
+/// assert!(try_range(1, 7).is_ok());
+/// assert_eq!(try_range(5, 5), Err(RangeError::Empty { bound: 5 }));
+/// assert_eq!(try_range(7, 1), Err(RangeError::Inverted { low: 7, high: 1 }));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn try_range(low: u64, high: u64) -> Result<u64, RangeError> {
+	Global.try_range(low, high)
+}
+
+/// Will generate a uniform [`u64`] in `[min(a, b), max(a, b))`, or `a` if the two are equal
+///
+/// Never panics: bounds in the wrong order are silently swapped and an empty range gives its bound.
+/// Meant for bounds typed in by users. Use [`try_range`] to report bad bounds instead of normalizing them.
+///
+/// # Example
+/// ```
+/// use hel_random::saturating_range;
+///
+/// let r = saturating_range(20, 10);
+///
+/// assert!((10..20).contains(&r));
+/// assert_eq!(saturating_range(5, 5), 5);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn saturating_range(a: u64, b: u64) -> u64 {
+	Global.saturating_range(a, b)
+}
+
+/// Will generate a uniformly chosen multiple of `step` in `[low, high)`
+///
+/// The values are aligned to `step` itself, not to `low`, e.g. page offsets or grid coordinates.
+/// One bounded draw over the number of multiples, no rejection on top of [`Source::below`].
+///
+/// # Panics
+/// If `step` is `0` or no multiple of `step` lies in `[low, high)`
+///
+/// # Example
+/// ```
+/// use hel_random::range_step;
+///
+/// let offset = range_step(1000, 100_000, 4096);
+///
+/// assert_eq!(offset % 4096, 0);
+/// assert!((1000..100_000).contains(&offset));
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn range_step(low: u64, high: u64, step: u64) -> u64 {
+	Global.range_step(low, high, step)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn range_step_from<S: Source>(source: &mut S, low: u64, high: u64, step: u64) -> u64 {
+	assert!(step > 0, "step must be positive");
+
+	let first = low.div_ceil(step).checked_mul(step);
+	let last = high.checked_sub(1).map(|h| h / step * step);
+
+	match (first, last) {
+		(Some(first), Some(last)) if first <= last => {
+			first + source.below((last - first) / step + 1) * step
+		}
+		_ => panic!("no multiple of {step} in [{low}, {high})"),
+	}
+}
+
+/// Integers that [`fill_range`] can fill, all primitives up to 64 bits
+pub trait RangeInt: Copy + PartialOrd + std::fmt::Display {
+	/// Will return `high - low` as an unsigned width, `low < high`
+	fn width(low: Self, high: Self) -> u64;
+
+	/// Will return `low + offset`, `offset` is less than the width
+	fn offset(low: Self, offset: u64) -> Self;
+}
+
+macro_rules! range_int {
+	($($type: ty => $unsigned: ty),*) => {$(
+		impl RangeInt for $type {
+			#[inline(always)]
+			fn width(low: Self, high: Self) -> u64 {
+				high.wrapping_sub(low) as $unsigned as u64
+			}
+
+			#[inline(always)]
+			fn offset(low: Self, offset: u64) -> Self {
+				low.wrapping_add(offset as $type)
+			}
+		}
+	)*};
+}
+
+range_int!(
+	u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+	i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize
+);
+
+/// Will fill `buf` with uniform values in `[low, high)`
+///
+/// The rejection threshold of Lemire's method is computed once for the whole slice,
+/// which makes it faster than calling [`range`] per element.
+///
+/// # Panics
+/// If `low >= high`
+///
+/// # Example
+/// ```
+/// use hel_random::fill_range;
+///
+/// let mut temps = [0i16; 365];
+/// fill_range(&mut temps, -30, 40);
+///
+/// assert!(temps.iter().all(|t| (-30..40).contains(t)));
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn fill_range<T: RangeInt>(buf: &mut [T], low: T, high: T) {
+	Global.fill_range(buf, low, high)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn fill_range_from<S: Source, T: RangeInt>(source: &mut S, buf: &mut [T], low: T, high: T) {
+	assert!(low < high, "range [{low}, {high}) is empty or inverted");
+
+	let width = T::width(low, high);
+	let threshold = width.wrapping_neg() % width;
+
+	for x in buf {
+		let mut m = source.next_u64() as u128 * width as u128;
+
+		while (m as u64) < threshold {
+			m = source.next_u64() as u128 * width as u128;
+		}
+
+		*x = T::offset(low, (m >> 64) as u64);
+	}
+}
+
+/// Will generate a uniform [`u64`] in `range`
+///
+/// # Panics
+/// If `range` is empty
+///
+/// # Example
+/// ```
+/// use hel_random::point_in;
+///
+/// let ports = 1024..49152;
+/// let port = point_in(ports.clone());
+///
+/// assert!(ports.contains(&port));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn point_in(range: Range<u64>) -> u64 {
+	Global.point_in(range)
+}
+
+/// Will generate a uniform [`u64`] in `range`, both ends included
+///
+/// # Panics
+/// If `range` is empty
+///
+/// # Example
+/// ```
+/// use hel_random::point_in_inclusive;
+///
+/// let roll = point_in_inclusive(1..=6);
+///
+/// assert!((1..=6).contains(&roll));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn point_in_inclusive(range: RangeInclusive<u64>) -> u64 {
+	Global.point_in_inclusive(range)
+}
+
+/// Will generate a uniform [`u64`] in `1..=n`, for dice and one-based ids
+///
+/// # Panics
+/// If `n == 0`
+///
+/// # Example
+/// ```
+/// use hel_random::nonzero_below;
+///
+/// let d6 = nonzero_below(6);
+///
+/// assert!((1..=6).contains(&d6));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn nonzero_below(n: u64) -> u64 {
+	Global.nonzero_below(n)
+}
+
+/// Will generate a random [`u64`] that is not in `blocked`, or [`None`] after `max_tries` draws hit it
+///
+/// The explicit bound keeps a nearly full space from looping forever
+///
+/// # Example
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hel_random::u64_excluding;
+///
+/// let reserved = HashSet::from([0, 1, u64::MAX]);
+/// let id = u64_excluding(&reserved, 10).unwrap();
+///
+/// assert!(!reserved.contains(&id));
+/// assert_eq!(u64_excluding(&reserved, 0), None);
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn u64_excluding(blocked: &HashSet<u64>, max_tries: usize) -> Option<u64> {
+	Global.u64_excluding(blocked, max_tries)
+}
+
+/// Will generate a [`u64`] uniformly over the union of `intervals`, or [`None`] if they are all empty
+///
+/// Every interval is weighted by its width, so each value in the union is equally likely.
+/// Intervals are expected to be disjoint, values in an overlap are twice as likely.
+///
+/// # Example
+/// ```
+/// use hel_random::range_multi;
+///
+/// // A registered or dynamic port, but not a well-known one
+/// let port = range_multi(&[1024..49152, 49152..65536]).unwrap();
+/// assert!((1024..65536).contains(&port));
+///
+/// assert_eq!(range_multi(&[]), None);
+/// assert_eq!(range_multi(&[5..5, 7..8]), Some(7));
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn range_multi(intervals: &[Range<u64>]) -> Option<u64> {
+	Global.range_multi(intervals)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn range_multi_from<S: Source>(source: &mut S, intervals: &[Range<u64>]) -> Option<u64> {
+	let width = |r: &Range<u64>| r.end.saturating_sub(r.start) as u128;
+
+	// Many wide intervals can add up past `u64::MAX`
+	let total: u128 = intervals.iter().map(width).sum();
+
+	if total == 0 {
+		return None;
+	}
+
+	let mut n = below_u128_from(source, total);
+
+	for r in intervals {
+		let w = width(r);
+
+		if n < w {
+			return Some(r.start + n as u64);
+		}
+
+		n -= w;
+	}
+
+	unreachable!("the draw is below the total width")
+}
+
+/// Will return `n` fair coin flips packed into the low bits of a [`u64`], the rest is zeroed
+///
+/// A single masked draw, much faster than calling [`bool()`] `n` times
+///
+/// # Panics
+/// If `n > 64`
+///
+/// # Example
+/// ```
+/// use hel_random::coin_flips;
+///
+/// let flips = coin_flips(10);
+///
+/// assert!(flips < 1 << 10);
+/// println!("heads = {}", flips.count_ones());
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn coin_flips(n: u32) -> u64 {
+	Global.coin_flips(n)
+}
+
+/// Will generate a uniform [`u64`] in `[0, 2^N)`, a width past 64 is rejected at compile time
+///
+/// The top `N` bits of one draw, with the shift known at compile time. Handy for fixed width ids.
+///
+/// # Example
+/// ```
+/// use hel_random::bits;
+///
+/// let id = bits::<12>();
+///
+/// assert!(id < 1 << 12);
+/// assert_eq!(bits::<0>(), 0);
+/// ```
+///
+/// ```compile_fail
+/// let too_wide = hel_random::bits::<65>();
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn bits<const N: u32>() -> u64 {
+	Global.bits::<N>()
+}
+
+/// Will generate a [`u64`] with the bits of a fresh draw in reverse order
+///
+/// A convenience for code that truncates to the low bits, e.g. `x as u8` or `x % 1024` hashing.
+/// The high bits of xoshiro256++ are slightly stronger, reversing moves them to the bottom.
+/// Code that keeps the whole value gains nothing from it, use [`u64`] there.
+///
+/// # Example
+/// ```
+/// use hel_random::{seed, u64, u64_reversed};
+///
+/// seed(7);
+/// let plain = u64();
+///
+/// seed(7);
+/// assert_eq!(u64_reversed(), plain.reverse_bits());
+///
+/// let slot = u64_reversed() as u8;
+/// println!("slot = {slot}");
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn u64_reversed() -> u64 {
+	Global.u64_reversed()
+}
+
+/// Will return the bytes of a fresh [`u64`] draw in little-endian order
+///
+/// # Example
+/// ```
+/// use hel_random::next_u64_le;
+///
+/// let bytes = next_u64_le();
+/// println!("r = {}", u64::from_le_bytes(bytes));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn next_u64_le() -> [u8; 8] {
+	Global.next_u64_le()
+}
+
+/// Will return the bytes of a fresh [`u64`] draw in big-endian (network) order
+///
+/// # Example
+/// ```
+/// use hel_random::next_u64_be;
+///
+/// let bytes = next_u64_be();
+/// println!("r = {}", u64::from_be_bytes(bytes));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn next_u64_be() -> [u8; 8] {
+	Global.next_u64_be()
+}
+
+/// Will fill `buf` with random bytes
+///
+/// Every 8 bytes come from one [`u64`] draw in little-endian order, see [`next_u64_le`]
+///
+/// # Example
+/// ```
+/// use hel_random::fill_bytes;
+///
+/// let mut key = [0u8; 32];
+/// fill_bytes(&mut key);
+///
+/// assert!(key.iter().any(|&b| b != 0));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn fill_bytes(buf: &mut [u8]) {
+	Global.fill_bytes(buf);
+}
+
+/// Will generate a byte vector of a uniform length in `0..=max`, filled with [`fill_bytes`]
+///
+/// Meant for fuzzing, empty and short inputs come up as often as long ones
+///
+/// # Example
+/// ```
+/// use hel_random::random_bytes_up_to;
+///
+/// let input = random_bytes_up_to(64);
+///
+/// assert!(input.len() <= 64);
+/// ```
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn random_bytes_up_to(max: usize) -> Vec<u8> {
+	Global.random_bytes_up_to(max)
+}
+
+/// Will generate a random byte array, filled 8 bytes per draw
+///
+/// Much faster than generating every byte separately, a `[u8; 32]` takes just four [`u64`] draws
+///
+/// # Example
+/// ```
+/// use hel_random::random_byte_array;
+///
+/// let token: [u8; 32] = random_byte_array();
+///
+/// assert!(token.iter().any(|&b| b != 0));
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn random_byte_array<const N: usize>() -> [u8; N] {
+	Global.random_byte_array()
+}
+
+/// Will XOR random bytes into `buf` in place, keeping the original data recoverable
+///
+/// This is handy for masking or whitening test inputs, it is NOT a cryptographic operation
+///
+/// # Example
+/// ```
+/// use hel_random::xor_bytes;
+///
+/// let original = *b"hello, world";
+/// let mut buf = original;
+/// xor_bytes(&mut buf);
+///
+/// assert_ne!(buf, original);
+/// ```
+#[inline]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub fn xor_bytes(buf: &mut [u8]) {
+	Global.xor_bytes(buf);
+}
+
+macro_rules! make_wrapper {
+	($($wrapper: ident)::+) => {
+		impl<T: Random> Random for $($wrapper)::+<T> {
+			#[doc = concat!("Will generate a [`", stringify!($($wrapper)::+), "`] around a random `T`")]
+			#[inline(always)]
+			fn random() -> Self {
+				$($wrapper)::+::new(T::random())
+			}
+
+			#[inline(always)]
+			fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+				$($wrapper)::+::new(T::random_from(source))
+			}
+		}
+	};
+}
+
+make_wrapper!(std::cell::Cell);
+make_wrapper!(std::cell::RefCell);
+
+impl<T: Random> Random for std::cmp::Reverse<T> {
+	/// Will generate a [`Reverse`](std::cmp::Reverse) around a random `T`
+	#[inline(always)]
+	fn random() -> Self {
+		std::cmp::Reverse(T::random())
+	}
+
+	#[inline(always)]
+	fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+		std::cmp::Reverse(T::random_from(source))
+	}
+}
+
+// A single bounded draw of `k` in `0..2^BITS - 1`, no rejecting zeros
+macro_rules! make_nonzero {
+	($($nonzero: ident => $unsigned: ident),* $(,)?) => {$(
+		impl Random for std::num::$nonzero {
+			#[doc = concat!("Will generate a [`", stringify!($nonzero), "`](std::num::", stringify!($nonzero), "), every nonzero value equally likely")]
+			#[inline(always)]
+			fn random() -> Self {
+				Self::random_from(&mut Global)
+			}
+
+			#[inline]
+			fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+				let k = below_u128_from(source, $unsigned::MAX as u128) as $unsigned;
+
+				Self::new(k + 1).unwrap()
+			}
+		}
+	)*};
+
+	($($nonzero: ident => $signed: ident, $unsigned: ident),* $(,)?) => {$(
+		impl Random for std::num::$nonzero {
+			#[doc = concat!("Will generate a [`", stringify!($nonzero), "`](std::num::", stringify!($nonzero), "), every nonzero value equally likely")]
+			#[inline(always)]
+			fn random() -> Self {
+				Self::random_from(&mut Global)
+			}
+
+			#[inline]
+			fn random_from<S: Source + ?Sized>(source: &mut S) -> Self {
+				let k = below_u128_from(source, $unsigned::MAX as u128) as $unsigned;
+
+				// `MIN..=MAX - 1`, the non-negative half is shifted up past zero
+				let v = (k as $signed).wrapping_add($signed::MIN);
+
+				Self::new(if v >= 0 { v + 1 } else { v }).unwrap()
+			}
+		}
+	)*};
+}
+
+make_nonzero!(
+	NonZeroU8 => u8,
+	NonZeroU16 => u16,
+	NonZeroU32 => u32,
+	NonZeroU64 => u64,
+	NonZeroU128 => u128,
+	NonZeroUsize => usize,
+);
+make_nonzero!(
+	NonZeroI8 => i8, u8,
+	NonZeroI16 => i16, u16,
+	NonZeroI32 => i32, u32,
+	NonZeroI64 => i64, u64,
+	NonZeroI128 => i128, u128,
+	NonZeroIsize => isize, usize,
+);
+
+#[cfg(test)]
+mod tests {
+	extern crate test;
+
+	use std::collections::HashSet;
+
+	use test::Bencher;
+
+	use super::*;
+
+	#[test]
+	fn flip_a_coin_fairness() {
+		for flip in [bool, bool_decorrelated] {
+			flip_fairness(flip);
+		}
+	}
+
+	fn flip_fairness(flip: fn() -> bool) {
+		const TRIES: i64 = 1_000_000;
+
+		let mut balance = 0;
+
+		flip();
+
+		let state = with_state(|state| *state);
+
+		println!("State: {}", format_state(&state));
+		println!(
+			"Population: {}",
+			state.iter().fold(0, |acc, s| acc + s.count_ones())
+		);
+
+		for _ in 0..TRIES {
+			balance += if flip() { 1 } else { -1 };
+		}
+
+		println!("Fairness: {balance}");
+		assert!(balance < (TRIES / 100));
+	}
+
+	macro_rules! make_test {
+		($test_name: ident, $bench_name: ident, $subject: ident) => {
+			#[bench]
+			fn $test_name(b: &mut Bencher) {
+				let _w = b.iter($subject);
+
+				assert!(u64() > 0);
+			}
+
+			#[bench]
+			#[ignore = "generating 1_000_000 result. Use 'cargo bench -- --ignored'"]
+			fn $bench_name(b: &mut Bencher) {
+				let _w = b.iter(|| {
+					let mut res = $subject();
+
+					for _ in 0..1_000_000 {
+						res = $subject();
+					}
+
+					res
+				});
+
+				assert!(u64() > 0);
+			}
+		};
+	}
+
+	make_test!(test_u128, bench_u128, u128);
+	make_test!(test_i128, bench_i128, i128);
+	make_test!(test_u64, bench_u64, u64);
+	make_test!(test_i64, bench_i64, i64);
+	make_test!(test_u32, bench_u32, u32);
+	make_test!(test_i32, bench_i32, i32);
+	make_test!(test_u16, bench_u16, u16);
+	make_test!(test_i16, bench_i16, i16);
+	make_test!(test_u8, bench_u8, u8);
+	make_test!(test_i8, bench_i8, i8);
+	make_test!(test_f64, bench_f64, f64);
+	make_test!(test_f32, bench_f32, f32);
+	make_test!(test_char, bench_char, char);
+	make_test!(test_bool, bench_bool, bool);
+	make_test!(
+		test_bool_decorrelated,
+		bench_bool_decorrelated,
+		bool_decorrelated
+	);
+
+	macro_rules! make_ignored {
+		($test_name: ident, $fn_name: ident) => {
+			#[test]
+			#[ignore = r#"to see output use "cargo t -- --ignored""#]
+			fn $test_name() {
+				$fn_name();
+
+				println!("{}", dump_state());
+
+				for _ in 0..100 {
+					println!("{}", $fn_name());
+				}
+
+				assert!(false);
+			}
+		};
+	}
+
+	make_ignored!(output_u128, u128);
+	make_ignored!(output_i128, i128);
+	make_ignored!(output_u64, u64);
+	make_ignored!(output_i64, i64);
+	make_ignored!(output_u32, u32);
+	make_ignored!(output_i32, i32);
+	make_ignored!(output_u16, u16);
+	make_ignored!(output_i16, i16);
+	make_ignored!(output_u8, u8);
+	make_ignored!(output_i8, i8);
+	make_ignored!(output_f64, f64);
+	make_ignored!(output_f32, f32);
+	make_ignored!(output_char, char);
+	make_ignored!(output_bool, bool);
+	make_ignored!(output_bool_decorrelated, bool_decorrelated);
+
+	#[test]
+	fn wrappers() {
+		use std::{
+			cell::{Cell, RefCell},
+			cmp::Reverse,
+			collections::BinaryHeap,
+		};
+
+		let heap: BinaryHeap<Reverse<u64>> = (0..100).map(|_| generate()).collect();
+		let sorted: Vec<_> = heap.into_sorted_vec().into_iter().map(|r| r.0).collect();
+		assert!(sorted.windows(2).all(|w| w[0] >= w[1]));
+
+		assert_ne!(Cell::<u64>::random().get(), Cell::<u64>::random().get());
+		assert_ne!(
+			RefCell::<u64>::random().take(),
+			RefCell::<u64>::random().take()
+		);
+	}
+
+	#[test]
+	fn nonzero_covers_all() {
+		use std::num::{NonZeroI128, NonZeroI8, NonZeroU128, NonZeroU64, NonZeroU8};
+
+		let unsigned: HashSet<u8> = (0..20_000).map(|_| NonZeroU8::random().get()).collect();
+		let signed: HashSet<i8> = (0..20_000).map(|_| NonZeroI8::random().get()).collect();
+
+		assert_eq!(unsigned, (1..=u8::MAX).collect());
+		assert_eq!(signed, (i8::MIN..=i8::MAX).filter(|&v| v != 0).collect());
+
+		// The top draw maps to the top value
+		let mut top = ByteReader::new(&[]);
+		assert_eq!(NonZeroU64::random_from(&mut top).get(), u64::MAX);
+		assert_eq!(NonZeroI8::random_from(&mut top).get(), i8::MAX);
+
+		// An exhausted reader is stuck at the top, the wide bounds must still accept it
+		assert_eq!(NonZeroU128::random_from(&mut top).get(), u128::MAX);
+		assert_eq!(NonZeroI128::random_from(&mut top).get(), i128::MAX);
+
+		// And the bottom one to the bottom
+		let mut bottom = ByteReader::new(&[0; 32]);
+		assert_eq!(NonZeroU128::random_from(&mut bottom).get(), 1);
+		assert_eq!(NonZeroI128::random_from(&mut bottom).get(), i128::MIN);
+
+		let negatives = (0..1_000)
+			.filter(|_| NonZeroI128::random().get() < 0)
+			.count();
+		assert!((400..600).contains(&negatives));
+	}
+
+	#[test]
+	fn clamped_saturates() {
+		const TRIES: usize = 10_000;
+
+		let mut endpoints = 0;
+
+		for _ in 0..TRIES {
+			let v: u8 = clamped(100, 110);
+			assert!((100..=110).contains(&v));
+
+			endpoints += (v == 100 || v == 110) as usize;
+		}
+
+		// 246 of the 256 bytes land on an endpoint
+		let ratio = endpoints as f64 / TRIES as f64;
+		println!("Endpoint ratio: {ratio}");
+		assert!((ratio - 246.0 / 256.0).abs() < 0.02);
+
+		assert_eq!(clamped(5i64, 5), 5);
+	}
+
+	#[test]
+	fn monotonic_gaps() {
+		let start = SystemTime::UNIX_EPOCH;
+		let max_gap = Duration::from_millis(2);
+		let times = monotonic_times(start, max_gap, 1_000);
+
+		assert_eq!(times.len(), 1_000);
+
+		let mut prev = start;
+		for t in times {
+			let gap = t.duration_since(prev).unwrap();
+			assert!(gap > Duration::ZERO && gap <= max_gap);
+
+			prev = t;
+		}
+
+		let tiny = monotonic_times(start, Duration::from_nanos(1), 3);
+		assert_eq!(
+			tiny[2].duration_since(start).unwrap(),
+			Duration::from_nanos(3)
+		);
+		assert!(monotonic_times(start, max_gap, 0).is_empty());
+	}
+
+	#[test]
+	fn monotonic_overflow() {
+		// The largest `SystemTime`, found bit by bit since it differs between platforms
+		let mut limit = SystemTime::UNIX_EPOCH;
+		for bit in (0..64).rev() {
+			limit = limit
+				.checked_add(Duration::from_secs(1 << bit))
+				.unwrap_or(limit);
+		}
+		for bit in (0..30).rev() {
+			limit = limit
+				.checked_add(Duration::from_nanos(1 << bit))
+				.unwrap_or(limit);
+		}
+
+		// One nanosecond gaps reach the very limit, then stay there
+		let start = limit - Duration::from_nanos(3);
+		let times = monotonic_times(start, Duration::from_nanos(1), 6);
+		assert!(times[..3].windows(2).all(|w| w[0] < w[1]));
+		assert!(times[2..].iter().all(|&t| t == limit));
+
+		// Larger gaps overflow short of it, and never increase again
+		let start = limit - Duration::from_micros(1);
+		for _ in 0..100 {
+			let times = monotonic_times(start, Duration::from_millis(1), 10);
+			let stop = times
+				.windows(2)
+				.position(|w| w[0] == w[1])
+				.unwrap_or(times.len());
+
+			assert!(times[..stop].windows(2).all(|w| w[0] < w[1]));
+			assert!(times[stop..].iter().all(|&t| t == times[stop]));
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn monotonic_zero_gap() {
+		monotonic_times(SystemTime::UNIX_EPOCH, Duration::ZERO, 1);
+	}
+
+	#[test]
+	fn with_probability_calls() {
+		const TRIES: usize = 100_000;
+
+		let mut calls = 0;
+		let hits = (0..TRIES)
+			.filter_map(|_| with_probability(0.3, || calls += 1))
+			.count();
+
+		assert_eq!(hits, calls);
+
+		let ratio = calls as f64 / TRIES as f64;
+		println!("Call ratio: {ratio}");
+		assert!((ratio - 0.3).abs() < 0.01);
+	}
+
+	#[test]
+	#[should_panic]
+	fn with_probability_above_one() {
+		with_probability(1.5, || ());
+	}
+
+	#[test]
+	fn bits_widths() {
+		let mut seen = 0;
+
+		for _ in 0..1_000 {
+			let b = bits::<20>();
+			assert!(b < 1 << 20);
+
+			seen |= b;
+		}
+
+		assert_eq!(seen, (1 << 20) - 1);
+		assert_eq!(bits::<0>(), 0);
+		assert!((0..100).any(|_| bits::<64>() >> 63 == 1));
+	}
+
+	#[test]
+	fn widening_products() {
+		assert_eq!(widening_mul_u128(0, u128::MAX), (0, 0));
+		assert_eq!(widening_mul_u128(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+		assert_eq!(widening_mul_u128(1 << 127, 4), (2, 0));
+
+		for _ in 0..1_000 {
+			let (a, b) = (u64() as u128, u64() as u128);
+			assert_eq!(widening_mul_u128(a, b), (0, a * b));
+		}
+	}
+
+	#[test]
+	fn wide_bounds_terminate_on_stuck_sources() {
+		let bounds = [u64::MAX as u128 + 1, 3 << 100, (1 << 127) + 1, u128::MAX];
+
+		for n in bounds {
+			// An exhausted reader maps to the top, zeros to the bottom
+			assert_eq!(below_u128_from(&mut ByteReader::new(&[]), n), n - 1);
+			assert_eq!(below_u128_from(&mut ByteReader::new(&[0; 16]), n), 0);
+		}
+
+		assert_eq!(u128::random_from(&mut ByteReader::new(&[])), u128::MAX);
+	}
+
+	#[test]
+	fn wide_bounds_are_uniform() {
+		const TRIES: usize = 60_000;
+
+		// Three buckets of a bound past `u64`, the rejection zone is tiny but present
+		let n = 3 << 100;
+		let mut counts = [0usize; 3];
 
-		println!("{:?}", &res);
+		for _ in 0..TRIES {
+			let r = below_u128_from(&mut Global, n);
+			assert!(r < n);
+
+			counts[(r >> 100) as usize] += 1;
+		}
+
+		println!("Counts: {counts:?}");
+		assert!(counts
+			.iter()
+			.all(|&c| (c as f64 / TRIES as f64 - 1.0 / 3.0).abs() < 0.01));
+	}
+
+	#[test]
+	fn random_or_sentinels() {
+		const TRIES: usize = 100_000;
+
+		let defaults = (0..TRIES)
+			.filter(|_| random_or(0.25, u64::MAX) == u64::MAX)
+			.count();
+		let freq = defaults as f64 / TRIES as f64;
+
+		println!("Defaults: {freq}");
+		assert!((freq - 0.25).abs() < 0.01);
+		assert!((0..1_000).all(|_| random_or(0.0, u64::MAX) != u64::MAX));
+	}
+
+	#[test]
+	#[should_panic]
+	fn random_or_invalid() {
+		random_or(-0.1, 0u8);
+	}
+
+	#[test]
+	fn variants_all_reachable() {
+		#[derive(Debug, Clone, Copy, PartialEq)]
+		enum Dir {
+			North,
+			East,
+			South,
+			West,
+		}
+
+		impl RandomVariant for Dir {
+			const COUNT: usize = 4;
+
+			fn from_index(i: usize) -> Self {
+				[Dir::North, Dir::East, Dir::South, Dir::West][i]
+			}
+		}
+
+		let mut seen = [false; Dir::COUNT];
+
+		for _ in 0..1_000 {
+			seen[random_variant::<Dir>() as usize] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+	}
+
+	#[test]
+	#[should_panic]
+	fn variant_of_empty_type() {
+		enum Never {}
+
+		impl RandomVariant for Never {
+			const COUNT: usize = 0;
+
+			fn from_index(_: usize) -> Self {
+				unreachable!()
+			}
+		}
+
+		random_variant::<Never>();
+	}
+
+	#[test]
+	fn u128_bit_balance() {
+		// Deviation from 1/2 of every bit position over `draws` draws
+		fn deviations(draws: usize, mut draw: impl FnMut() -> u128) -> [f64; 128] {
+			let mut counts = [0usize; 128];
+
+			for _ in 0..draws {
+				let x = draw();
+
+				for (bit, count) in counts.iter_mut().enumerate() {
+					*count += (x >> bit) as usize & 1;
+				}
+			}
+
+			counts.map(|c| (c as f64 / draws as f64 - 0.5).abs())
+		}
+
+		// What `u128` used to do: one step, then the raw state words instead of two `++` outputs
+		fn raw_state(state: &mut StateType) -> u128 {
+			xoshiro256pp(state);
+
+			state[0].wrapping_add(state[2]) as u128
+				| ((state[1].wrapping_add(state[3]) as u128) << 64)
+		}
+
+		// The global generator's step, on a state of our choosing
+		struct Xoshiro(StateType);
+
+		impl Source for Xoshiro {
+			fn next_u64(&mut self) -> u64 {
+				let x = scramble(&self.0);
+				xoshiro256pp(&mut self.0);
+				x
+			}
+		}
+
+		// Out of a state with a single bit set the raw words stay sparse for a while, the scrambled
+		// outputs much less so. Both are fixed sequences, so the comparison is deterministic.
+		let mut state = [1, 0, 0, 0];
+		let raw: f64 = deviations(32, || raw_state(&mut state)).iter().sum::<f64>() / 128.0;
+
+		let mut source = Xoshiro([1, 0, 0, 0]);
+		let composed = deviations(32, || u128::random_from(&mut source))
+			.iter()
+			.sum::<f64>()
+			/ 128.0;
+
+		println!("Mean deviation after a sparse state, raw: {raw}, composed: {composed}");
+		assert!(composed <= raw);
+
+		// From a well mixed state both look balanced, the new one has to be within noise
+		let mut rng = Rng::from_seed(5);
+		let worst = deviations(100_000, || rng.generate())
+			.into_iter()
+			.fold(0.0, f64::max);
+
+		println!("Worst deviation: {worst}");
+		// About 6 standard deviations
+		assert!(worst < 0.01);
+	}
+
+	#[test]
+	fn below_is_bounded() {
+		for n in [1, 2, 3, 7, 100, u64::MAX / 3, u64::MAX] {
+			for _ in 0..1_000 {
+				assert!(Global.below(n) < n);
+			}
+		}
+
+		let n = u64::MAX as u128 * 5;
+		for _ in 0..1_000 {
+			assert!(below_u128_from(&mut Global, n) < n);
+		}
+	}
+
+	#[test]
+	fn full_jitter_bounds() {
+		let base = Duration::from_millis(10);
+		let cap = Duration::from_secs(1);
+
+		for attempt in 0..8 {
+			let ceil = base * 2u32.pow(attempt);
+
+			for _ in 0..100 {
+				assert!(full_jitter(base, cap, attempt) <= ceil);
+			}
+		}
+
+		// Overflowing `base * 2^attempt` saturates at `cap`
+		let huge = Duration::MAX / 2;
+		for attempt in [31, 32, 100, u32::MAX] {
+			assert!(full_jitter(huge, cap, attempt) <= cap);
+		}
+
+		assert!(full_jitter(Duration::MAX, Duration::MAX, 0) > Duration::ZERO);
+		assert_eq!(full_jitter(Duration::ZERO, cap, 5), Duration::ZERO);
+
+		// A zero base stays zero however large the attempt
+		assert_eq!(full_jitter(Duration::ZERO, cap, 32), Duration::ZERO);
+		assert_eq!(full_jitter(Duration::ZERO, cap, u32::MAX), Duration::ZERO);
+
+		// `1ns * 2^32` fits, it must not saturate at the much larger cap
+		let tiny = Duration::from_nanos(1);
+		let hour = Duration::from_secs(3600);
+		let ceil = Duration::from_nanos(1 << 32);
+		assert!((0..100).all(|_| full_jitter(tiny, hour, 32) <= ceil));
+
+		// `1ns * 2^u32::MAX` doesn't, that one does saturate
+		let delays: Vec<Duration> = (0..100)
+			.map(|_| full_jitter(tiny, hour, u32::MAX))
+			.collect();
+		assert!(delays.iter().all(|&d| d <= hour));
+		assert!(delays.iter().any(|&d| d > ceil));
+	}
+
+	#[test]
+	fn quantized_durations() {
+		let mut max_seen = false;
+
+		for _ in 0..1_000 {
+			let ms = duration_millis(3);
+			assert!(ms <= Duration::from_millis(3));
+			assert_eq!(ms.subsec_nanos() % 1_000_000, 0);
+			max_seen |= ms == Duration::from_millis(3);
+
+			let secs = duration_secs(10);
+			assert!(secs <= Duration::from_secs(10));
+			assert_eq!(secs.subsec_nanos(), 0);
+		}
+
+		assert!(max_seen);
+		assert_eq!(duration_secs(0), Duration::ZERO);
+		duration_secs(u64::MAX);
+		duration_millis(u64::MAX);
+	}
+
+	#[test]
+	fn fill_and_xor_bytes() {
+		for len in [0, 1, 7, 8, 9, 31, 32, 1000] {
+			let mut buf = vec![0u8; len];
+			fill_bytes(&mut buf);
+
+			if len >= 8 {
+				assert!(buf.iter().any(|&b| b != 0));
+			}
+		}
+
+		let mut buf = [0xffu8; 64];
+		xor_bytes(&mut buf);
+
+		let flipped: u32 = buf.iter().map(|b| b.count_zeros()).sum();
+		println!("Flipped: {flipped} of 512");
+		assert!((128..384).contains(&flipped));
+	}
+
+	#[test]
+	fn auto_reseed_fires() {
+		let original = [1, 2, 3, 4];
+		let mut state = original;
+		let mut steps = 0;
+		let mut epoch = Some(Instant::now());
+
+		// So the time since `epoch` can't be zero
+		std::thread::sleep(Duration::from_millis(1));
+
+		auto_reseed(&mut state, &mut steps, 3, &mut epoch);
+		auto_reseed(&mut state, &mut steps, 3, &mut epoch);
+		assert_eq!(steps, 2);
+		assert_eq!(state, original);
+
+		auto_reseed(&mut state, &mut steps, 3, &mut epoch);
+		assert_eq!(steps, 0);
+		assert_ne!(state[0], original[0]);
+		assert_eq!(state[1..], original[1..]);
+	}
+
+	#[test]
+	fn mix_in_never_zeroes() {
+		let mut state = [7u64.wrapping_mul(0x9e3779b97f4a7c15), 0, 0, 0];
+		mix_in(&mut state, 7);
+		assert_ne!(state, [0; STATE_SIZE]);
+
+		let mut state = [1, 2, 3, 4];
+		mix_in(&mut state, 0);
+		assert_eq!(state, [1, 2, 3, 4]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn zero_state() {
+		set_state([0; STATE_SIZE]);
+	}
+
+	#[test]
+	fn lazy_seeding() {
+		// What another constructor would see if it ran before ours
+		let mut state = [0; STATE_SIZE];
+
+		lazy_seed(&mut state);
+		assert_ne!(state, [0; STATE_SIZE]);
+
+		let seeded = state;
+		lazy_seed(&mut state);
+		assert_eq!(state, seeded);
+
+		xoshiro256pp(&mut state);
+		assert_ne!(state, [0; STATE_SIZE]);
+	}
+
+	#[test]
+	fn initialized_before_main() {
+		assert!(is_initialized());
+
+		ensure_initialized();
+		assert!(is_initialized());
+	}
+
+	#[test]
+	fn state_format() {
+		assert_eq!(
+			format_state(&[0, 1, 0xdead_beef, u64::MAX]),
+			"0000000000000000 0000000000000001 00000000deadbeef ffffffffffffffff"
+		);
+	}
+
+	#[test]
+	fn u64_byte_order() {
+		let le = next_u64_le();
+		let be = next_u64_be();
+
+		assert_ne!(le, be);
+		assert_ne!(le, [0; 8]);
+		assert_ne!(be, [0; 8]);
+	}
+
+	#[test]
+	fn floats_in_unit_interval() {
+		for _ in 0..10_000 {
+			assert!((0.0..1.0).contains(&f64()));
+			assert!((0.0..1.0).contains(&f32()));
+		}
+	}
+
+	#[test]
+	fn point_in_bounds() {
+		let mut seen = [false; 3];
+
+		for _ in 0..1_000 {
+			seen[(point_in(10..13) - 10) as usize] = true;
+			assert!((5..=7).contains(&point_in_inclusive(5..=7)));
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(point_in(4..5), 4);
+		assert_eq!(point_in_inclusive(u64::MAX..=u64::MAX), u64::MAX);
+		point_in_inclusive(0..=u64::MAX);
+	}
+
+	#[test]
+	fn nonzero_dice() {
+		let mut seen = [false; 6];
+
+		for _ in 0..1_000 {
+			seen[nonzero_below(6) as usize - 1] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(nonzero_below(1), 1);
+		assert!(nonzero_below(u64::MAX) > 0);
+	}
+
+	#[test]
+	fn excluding_blocked() {
+		let blocked: HashSet<u64> = (0..1_000).map(|_| u64()).collect();
+
+		for _ in 0..1_000 {
+			let r = u64_excluding(&blocked, 1).unwrap();
+			assert!(!blocked.contains(&r));
+		}
+
+		assert_eq!(u64_excluding(&HashSet::new(), 0), None);
+	}
+
+	#[test]
+	fn multi_ranges_are_uniform() {
+		const TRIES: usize = 60_000;
+
+		// Widths 1, 0, 2: values 0, 10 and 11 equally likely
+		let mut counts = [0usize; 3];
+
+		for _ in 0..TRIES {
+			match range_multi(&[0..1, 5..5, 10..12]).unwrap() {
+				0 => counts[0] += 1,
+				10 => counts[1] += 1,
+				11 => counts[2] += 1,
+				r => panic!("out of range: {r}"),
+			}
+		}
+
+		println!("Counts: {counts:?}");
+		assert!(counts
+			.iter()
+			.all(|&c| (c as f64 / TRIES as f64 - 1.0 / 3.0).abs() < 0.01));
+
+		#[allow(clippy::reversed_empty_ranges)]
+		let inverted = [3..1, 4..4];
+		assert_eq!(range_multi(&inverted), None);
+		assert!(range_multi(&[0..u64::MAX, 0..u64::MAX]).is_some());
+	}
+
+	#[test]
+	#[should_panic]
+	fn nonzero_below_zero() {
+		nonzero_below(0);
+	}
+
+	#[test]
+	fn ranges() {
+		for _ in 0..1_000 {
+			assert!((3..6).contains(&range(3, 6)));
+			assert!((0..u64::MAX).contains(&try_range(0, u64::MAX).unwrap()));
+		}
+
+		assert_eq!(range(u64::MAX - 1, u64::MAX), u64::MAX - 1);
+		assert_eq!(try_range(0, 0), Err(RangeError::Empty { bound: 0 }));
+		assert_eq!(
+			try_range(2, 1).unwrap_err().to_string(),
+			"range [2, 1) is inverted"
+		);
+	}
+
+	#[test]
+	fn saturating_ranges() {
+		for _ in 0..1_000 {
+			assert!((3..6).contains(&saturating_range(3, 6)));
+			assert!((3..6).contains(&saturating_range(6, 3)));
+		}
+
+		assert_eq!(saturating_range(9, 9), 9);
+		assert_eq!(saturating_range(u64::MAX, u64::MAX - 1), u64::MAX - 1);
+	}
+
+	#[test]
+	fn stepped_ranges() {
+		let mut seen = HashSet::new();
+
+		for _ in 0..1_000 {
+			let r = range_step(5, 31, 5);
+			assert!((5..31).contains(&r) && r.is_multiple_of(5));
+
+			seen.insert(r);
+		}
+
+		assert_eq!(seen, HashSet::from([5, 10, 15, 20, 25, 30]));
+
+		assert_eq!(range_step(1, 8, 7), 7);
+		assert_eq!(range_step(0, u64::MAX, u64::MAX / 2) % (u64::MAX / 2), 0);
+		assert_eq!(range_step(u64::MAX - 1, u64::MAX, 1), u64::MAX - 1);
+	}
+
+	#[test]
+	#[should_panic = "no multiple of 10 in [11, 20)"]
+	fn step_without_multiple() {
+		range_step(11, 20, 10);
+	}
+
+	#[test]
+	#[should_panic]
+	fn step_zero() {
+		range_step(0, 10, 0);
+	}
+
+	#[test]
+	fn fill_ranges() {
+		let mut bytes = [0i8; 4096];
+		fill_range(&mut bytes, i8::MIN, i8::MAX);
+		assert!(bytes.iter().all(|&b| b != i8::MAX));
+		assert!(bytes.contains(&i8::MIN) && bytes.contains(&(i8::MAX - 1)));
+
+		let mut words = [0u64; 1000];
+		fill_range(&mut words, u64::MAX - 3, u64::MAX);
+		assert!(words.iter().all(|w| (u64::MAX - 3..u64::MAX).contains(w)));
+
+		let mut signed = [0isize; 1000];
+		fill_range(&mut signed, -5, 5);
+		assert!(signed.iter().all(|s| (-5..5).contains(s)));
+
+		let mut one = [7u16; 10];
+		fill_range(&mut one, 3, 4);
+		assert_eq!(one, [3; 10]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn fill_range_empty() {
+		fill_range(&mut [0u32; 4], 5, 5);
+	}
+
+	#[test]
+	#[should_panic = "range [4, 4) is empty"]
+	fn range_empty() {
+		range(4, 4);
+	}
+
+	#[test]
+	#[should_panic]
+	fn point_in_empty() {
+		point_in(5..5);
+	}
+
+	#[test]
+	fn coin_flips_masked() {
+		assert_eq!(coin_flips(0), 0);
+
+		let mut heads = [0u32; 64];
+
+		for n in 1..=64 {
+			for _ in 0..1_000 {
+				let flips = coin_flips(n);
+
+				if n < 64 {
+					assert!(flips < 1 << n);
+				}
+
+				heads[n as usize - 1] += flips.count_ones();
+			}
+		}
+
+		// On average half of the `n` flips are heads
+		for (i, &h) in heads.iter().enumerate() {
+			let expected = (i as f64 + 1.0) * 500.0;
+			assert!((h as f64 - expected).abs() < expected * 0.2 + 50.0);
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn coin_flips_too_many() {
+		coin_flips(65);
+	}
+
+	#[test]
+	fn mix64_reference() {
+		// First two outputs of the reference SplitMix64 seeded with 0
+		assert_eq!(mix64(0x9e3779b97f4a7c15), 0xe220a8397b1dcdaf);
+		assert_eq!(mix64(0x3c6ef372fe94f82a), 0x6e789e6aa1b965f4);
+		assert_eq!(mix64(0), 0);
+	}
+
+	#[test]
+	fn byte_arrays() {
+		let a: [u8; 32] = random_byte_array();
+		let b: [u8; 32] = random_byte_array();
+		assert_ne!(a, b);
+
+		let empty: [u8; 0] = random_byte_array();
+		assert!(empty.is_empty());
+
+		let odd: [u8; 13] = random_byte_array();
+		assert!(odd.iter().any(|&b| b != 0));
+	}
+
+	#[test]
+	fn bytes_up_to() {
+		let mut seen = [false; 5];
+
+		for _ in 0..1_000 {
+			seen[random_bytes_up_to(4).len()] = true;
+		}
+
+		assert_eq!(seen, [true; 5]);
+		assert!(random_bytes_up_to(0).is_empty());
+	}
+
+	#[test]
+	#[ignore = "spawned by seed_from_env"]
+	fn seed_from_env_child() {
+		println!("values={},{}", u64(), u64());
+	}
+
+	#[test]
+	fn seed_from_env() {
+		let run = |seed: Option<&str>| {
+			let mut cmd = std::process::Command::new(std::env::current_exe().unwrap());
+			cmd.args([
+				"--exact",
+				"tests::seed_from_env_child",
+				"--ignored",
+				"--nocapture",
+			]);
+			cmd.env(PRINT_SEED_VAR, "1").env_remove(SEED_VAR);
+
+			if let Some(seed) = seed {
+				cmd.env(SEED_VAR, seed);
+			}
+
+			let output = cmd.output().unwrap();
+			let stdout = String::from_utf8(output.stdout).unwrap();
+			let stderr = String::from_utf8(output.stderr).unwrap();
+
+			// The harness prints the test name on the same line
+			let values = stdout
+				.split("values=")
+				.nth(1)
+				.unwrap()
+				.lines()
+				.next()
+				.unwrap()
+				.to_owned();
+			let printed = stderr
+				.lines()
+				.find_map(|l| l.strip_prefix("HEL_RANDOM_SEED="))
+				.unwrap()
+				.to_owned();
+
+			(values, printed)
+		};
+
+		let (a, seed_a) = run(Some("42"));
+		let (b, seed_b) = run(Some(" 42\n"));
+		assert_eq!(seed_a, "42");
+		assert_eq!((a.as_str(), seed_b.as_str()), (b.as_str(), "42"));
+
+		// The printed seed of an unseeded run reproduces it
+		let (c, seed_c) = run(None);
+		let (d, _) = run(Some(&seed_c));
+		assert_eq!(c, d);
+		assert_ne!(a, c);
+
+		// Garbage in, heap garbage seeding out
+		let (e, _) = run(Some("not a number"));
+		assert_ne!(e, a);
+	}
+
+	#[test]
+	fn char_planes() {
+		let mut bmp = false;
+		let mut astral = false;
+
+		for _ in 0..10_000 {
+			let c = char();
+
+			bmp |= (c as u32) < 0x10000;
+			astral |= (c as u32) >= 0x10000;
+		}
+
+		// ~94% of the scalar values are outside the BMP
+		assert!(bmp && astral);
+	}
+
+	#[test]
+	fn multithreaded() {
+		const THREADS: usize = 1024;
+		let mut threads = Vec::new();
+
+		for _ in 0..THREADS {
+			threads.push(std::thread::spawn(|| {
+				let mut res = u64();
+
+				for _ in 0..1_000 {
+					res = u64();
+				}
+
+				res
+			}))
+		}
+
+		let mut res: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+		res.sort();
+
+		println!("{:?}", res);
 
 		let set: HashSet<_> = HashSet::from_iter(res);
 
-		println!("{:?}", &set);
+		println!("{:?}", set);
 
 		assert_eq!(set.len(), THREADS);
 
 		// assert!(false);
 	}
+	#[test]
+	fn seeded_helpers() {
+		#[derive(Debug, PartialEq)]
+		enum Side {
+			Left,
+			Right,
+		}
+
+		impl RandomVariant for Side {
+			const COUNT: usize = 2;
+
+			fn from_index(i: usize) -> Self {
+				if i == 0 {
+					Side::Left
+				} else {
+					Side::Right
+				}
+			}
+		}
+
+		let run = || {
+			let mut rng = Rng::from_seed(144);
+			let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+			let mut buf = [0u16; 8];
+			rng.fill_range(&mut buf, 10, 20);
+			assert!(buf.iter().all(|b| (10..20).contains(b)));
+
+			let mut xored = [0xAA; 11];
+			rng.xor_bytes(&mut xored);
+
+			let misc = (
+				rng.random_or(0.5, 7u32),
+				rng.with_probability(0.5, || 3),
+				rng.clamped(10u8, 20),
+				rng.random_variant::<Side>(),
+				rng.full_jitter(Duration::from_millis(10), Duration::from_secs(1), 4),
+				rng.duration_millis(1_000),
+				rng.duration_secs(60),
+				rng.monotonic_times(start, Duration::from_secs(5), 4),
+				rng.saturating_range(50, 10),
+				rng.range_step(5, 31, 5),
+				rng.point_in(100..200),
+				rng.point_in_inclusive(0..=u64::MAX),
+			);
+			let bits = (
+				rng.nonzero_below(9),
+				rng.u64_excluding(&HashSet::from([1, 2, 3]), 5),
+				rng.range_multi(&[0..10, 100..110]),
+				rng.coin_flips(10),
+				rng.bits::<12>(),
+				rng.u64_reversed(),
+				rng.next_u64_le(),
+				rng.next_u64_be(),
+				rng.random_bytes_up_to(16),
+				rng.random_byte_array::<5>(),
+				rng.bool_decorrelated(),
+			);
+
+			(buf, xored, misc, bits)
+		};
+
+		assert_eq!(run(), run());
+	}
 }
@@ -0,0 +1,116 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::{Global, Random, Source};
+
+macro_rules! make_net {
+	($type: ty, |$source: ident| $from: block) => {
+		impl Random for $type {
+			#[doc = concat!("Will generate a random [`", stringify!($type), "`]")]
+			#[inline(always)]
+			fn random() -> Self {
+				Self::random_from(&mut Global)
+			}
+
+			#[inline]
+			fn random_from<S: Source + ?Sized>($source: &mut S) -> Self $from
+		}
+	};
+}
+
+make_net!(Ipv4Addr, |source| {
+	Ipv4Addr::from(source.next_u64() as u32)
+});
+make_net!(Ipv6Addr, |source| {
+	Ipv6Addr::from(u128::random_from(source))
+});
+make_net!(IpAddr, |source| {
+	match bool::random_from(source) {
+		true => IpAddr::V4(Ipv4Addr::random_from(source)),
+		false => IpAddr::V6(Ipv6Addr::random_from(source)),
+	}
+});
+
+make_net!(SocketAddrV4, |source| {
+	SocketAddrV4::new(Ipv4Addr::random_from(source), source.next_u64() as u16)
+});
+// Flow info and scope id are left at 0
+make_net!(SocketAddrV6, |source| {
+	SocketAddrV6::new(
+		Ipv6Addr::random_from(source),
+		source.next_u64() as u16,
+		0,
+		0,
+	)
+});
+make_net!(SocketAddr, |source| {
+	match bool::random_from(source) {
+		true => SocketAddr::V4(SocketAddrV4::random_from(source)),
+		false => SocketAddr::V6(SocketAddrV6::random_from(source)),
+	}
+});
+
+/// Will generate a random IPv4 endpoint with a random port
+///
+/// # Example
+/// ```
+/// use hel_random::random_socketaddr_v4;
+///
+/// let addr = random_socketaddr_v4();
+/// println!("addr = {addr}");
+/// ```
+#[inline]
+pub fn random_socketaddr_v4() -> SocketAddrV4 {
+	Global.random_socketaddr_v4()
+}
+
+/// Will generate a random IPv6 endpoint with a random port
+///
+/// # Example
+/// ```
+/// use hel_random::random_socketaddr_v6;
+///
+/// let addr = random_socketaddr_v6();
+/// println!("addr = {addr}");
+/// ```
+#[inline]
+pub fn random_socketaddr_v6() -> SocketAddrV6 {
+	Global.random_socketaddr_v6()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn socketaddr_families() {
+		let mut v4 = false;
+		let mut v6 = false;
+
+		for _ in 0..100 {
+			match SocketAddr::random() {
+				SocketAddr::V4(_) => v4 = true,
+				SocketAddr::V6(_) => v6 = true,
+			}
+		}
+
+		assert!(v4 && v6);
+		assert_ne!(random_socketaddr_v4(), random_socketaddr_v4());
+		assert_ne!(random_socketaddr_v6(), random_socketaddr_v6());
+	}
+
+	#[test]
+	fn seeded_addresses() {
+		let a: SocketAddr = crate::Rng::from_seed(3).generate();
+		let b: SocketAddr = crate::Rng::from_seed(3).generate();
+		assert_eq!(a, b);
+
+		let ip: IpAddr = crate::Rng::from_seed(4).generate();
+		assert_ne!(ip, crate::Rng::from_seed(5).generate::<IpAddr>());
+
+		let endpoints = || {
+			let mut rng = crate::Rng::from_seed(6);
+			(rng.random_socketaddr_v4(), rng.random_socketaddr_v6())
+		};
+		assert_eq!(endpoints(), endpoints());
+	}
+}
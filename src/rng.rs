@@ -0,0 +1,775 @@
+use crate::{
+	expand_seed_with, mix64, scramble, scramble_starstar, standard_normal_pair_from, stream_gamma,
+	xoshiro256pp, Source, StateType,
+};
+
+/// A self-contained xoshiro256++ generator with its own state
+///
+/// Unlike the global functions, an [`Rng`] is deterministic for a given seed.
+/// The helpers behind the free functions (`range`, `choose`, `shuffle`, the distributions, ...)
+/// are [`Source`] methods, and [`Rng::bool_decorrelated`] covers the one that reads the raw state,
+/// so every draw the global generator makes can be reproduced on a seeded one.
+/// Only managing the global state itself (`seed`, `set_state`, the test hooks, ...) has no equivalent.
+///
+/// # Example
+/// ```
+/// use hel_random::{Rng, Source};
+///
+/// let mut a = Rng::from_seed(42);
+/// let mut b = Rng::from_seed(42);
+///
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// assert_eq!(a.range(1, 7), b.range(1, 7));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+	state: StateType,
+	// Second standard normal sample of the last Box–Muller pair
+	spare: Option<f64>,
+	// Unconsumed low bits of the last draw made by `take_bits`
+	bits: u64,
+	bits_len: u32,
+	variant: Xoshiro,
+}
+
+/// The output function of an [`Rng`], both share the same xoshiro256 state transition
+///
+/// See [`RngBuilder::variant`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Xoshiro {
+	/// xoshiro256++, the default and what the global generator uses
+	#[default]
+	PlusPlus,
+	/// xoshiro256**, for reproducing sequences of other implementations
+	StarStar,
+}
+
+impl Rng {
+	/// Will create a generator with state expanded from `seed` using SplitMix64
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut rng = Rng::from_seed(0);
+	/// println!("r = {}", rng.next_u64());
+	/// ```
+	pub fn from_seed(seed: u64) -> Self {
+		Self::with_stream(seed, 0)
+	}
+
+	/// Will create a generator for stream `stream_id` of `seed`
+	///
+	/// The stream id picks the increment of the SplitMix64 seeding, so different ids give decorrelated
+	/// sequences from the same seed in O(1), which is handy for per-entity randomness.
+	/// Seed and stream don't simply cancel out, two pairs give the same state only if they are the same pair
+	/// (or, rarely, two ids map to the same increment).
+	/// Streams are independent starting points in the period, unlike jump-based splitting they are not
+	/// guaranteed to be non-overlapping, though with a 2^256 period an overlap is practically impossible.
+	///
+	/// Stream `0` is the same as [`Rng::from_seed`]
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut a = Rng::with_stream(42, 1);
+	/// let mut b = Rng::with_stream(42, 2);
+	///
+	/// assert_ne!(a.next_u64(), b.next_u64());
+	/// assert_eq!(Rng::with_stream(42, 0).next_u64(), Rng::from_seed(42).next_u64());
+	/// ```
+	pub fn with_stream(seed: u64, stream_id: u64) -> Self {
+		// `mix64(0) == 0`, so stream 0 runs the reference SplitMix64
+		let state = expand_seed_with(seed, stream_gamma(stream_id));
+
+		Self {
+			state,
+			spare: None,
+			bits: 0,
+			bits_len: 0,
+			variant: Xoshiro::PlusPlus,
+		}
+	}
+
+	/// Will reset the generator to the state of [`Rng::from_seed`]`(seed)`, keeping its [`Xoshiro`] variant
+	///
+	/// Cached values are dropped too, the Box–Muller spare of [`Rng::normal`] and the bits buffered
+	/// by [`Rng::take_bits`], so afterwards it behaves exactly like a freshly seeded generator.
+	/// SplitMix64 never expands a seed into the all-zero state.
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut rng = Rng::from_seed(1);
+	/// rng.normal(0.0, 1.0);
+	///
+	/// rng.reseed(2);
+	/// assert_eq!(rng.next_u64(), Rng::from_seed(2).next_u64());
+	/// ```
+	pub fn reseed(&mut self, seed: u64) {
+		*self = Self {
+			variant: self.variant,
+			..Self::from_seed(seed)
+		};
+	}
+
+	/// Will create `N` child generators, each seeded from a consecutive output of this one
+	///
+	/// The parent advances past all of them, so forking again gives new children.
+	/// Children are reproducible for a given parent state and decorrelated through SplitMix64 seeding.
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut parent = Rng::from_seed(1);
+	/// let [mut a, mut b, mut c] = parent.fork();
+	///
+	/// assert_ne!(a.next_u64(), b.next_u64());
+	/// assert_eq!(c.next_u64(), Rng::from_seed(1).fork::<3>()[2].next_u64());
+	/// ```
+	pub fn fork<const N: usize>(&mut self) -> [Rng; N] {
+		std::array::from_fn(|_| Rng::from_seed(self.next_u64()))
+	}
+
+	/// Will advance the generator by `2^128` steps, as if [`Rng::next_u64`] was called that many times
+	///
+	/// Jumping a copy `k` times gives `2^128` non-overlapping sequences of length `2^128`,
+	/// for guaranteed independent parallel streams. Costs 256 steps.
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut a = Rng::from_seed(8);
+	/// let mut b = a.clone();
+	/// b.jump();
+	///
+	/// assert_ne!(a.next_u64(), b.next_u64());
+	/// ```
+	pub fn jump(&mut self) {
+		const JUMP: StateType = [
+			0x180ec6d33cfd0aba,
+			0xd5a61266f0c9392c,
+			0xa9582618e03fc9aa,
+			0x39abdc4529b1661c,
+		];
+
+		let mut res = [0; 4];
+
+		for word in JUMP {
+			for bit in 0..64 {
+				if word >> bit & 1 == 1 {
+					res.iter_mut().zip(self.state).for_each(|(r, s)| *r ^= s);
+				}
+
+				xoshiro256pp(&mut self.state);
+			}
+		}
+
+		self.state = res;
+	}
+
+	/// Will return an endless iterator of random values drawn from this generator
+	///
+	/// The sequence is reproducible for a given seed, like any other use of the generator
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let a: Vec<u32> = Rng::from_seed(1).iter().take(5).collect();
+	/// let b: Vec<u32> = Rng::from_seed(1).iter().take(5).collect();
+	///
+	/// assert_eq!(a, b);
+	/// ```
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	pub fn iter<T: crate::Random>(&mut self) -> impl Iterator<Item = T> + '_ {
+		std::iter::repeat_with(move || T::random_from(self))
+	}
+
+	/// Will generate a random [`u64`]
+	#[inline]
+	pub fn next_u64(&mut self) -> u64 {
+		let res = match self.variant {
+			Xoshiro::PlusPlus => scramble(&self.state),
+			Xoshiro::StarStar => scramble_starstar(&self.state),
+		};
+		xoshiro256pp(&mut self.state);
+
+		res
+	}
+
+	/// Will generate a random [`f64`] in `[0, 1)`
+	#[inline]
+	pub fn f64(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+	}
+
+	/// Will return the next `n` bits (`1..=64`) of a bit stream in the low bits of a [`u64`]
+	///
+	/// Bits are buffered, so consuming 3 bits here and 5 bits there doesn't waste a whole draw each time.
+	/// The buffer is part of the generator state: it is cloned along with it, and it is independent of
+	/// other methods, a [`Rng::next_u64`] call neither uses nor drops buffered bits.
+	/// A freshly seeded generator starts with an empty buffer.
+	///
+	/// # Panics
+	/// If `n` is not in `1..=64`
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut rng = Rng::from_seed(5);
+	///
+	/// let tag = rng.take_bits(3);
+	/// let value = rng.take_bits(5);
+	///
+	/// assert!(tag < 8 && value < 32);
+	/// ```
+	pub fn take_bits(&mut self, n: u32) -> u64 {
+		assert!((1..=64).contains(&n), "can't take {n} bits");
+
+		let mask = u64::MAX >> (64 - n);
+
+		if n <= self.bits_len {
+			let res = self.bits & mask;
+
+			self.bits = self.bits.checked_shr(n).unwrap_or(0);
+			self.bits_len -= n;
+
+			return res;
+		}
+
+		// Whatever is left becomes the low part, the rest comes from a fresh draw
+		let have = self.bits_len;
+		let need = n - have;
+		let fresh = self.next_u64();
+
+		let res = (self.bits | fresh.checked_shl(have).unwrap_or(0)) & mask;
+
+		self.bits = fresh.checked_shr(need).unwrap_or(0);
+		self.bits_len = 64 - need;
+
+		res
+	}
+
+	/// Will generate a random [`bool`] by running a von Neumann extractor over the raw state bits,
+	/// see [`bool_decorrelated`](crate::bool_decorrelated)
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let a = Rng::from_seed(8).bool_decorrelated();
+	/// assert_eq!(a, Rng::from_seed(8).bool_decorrelated());
+	/// ```
+	pub fn bool_decorrelated(&mut self) -> bool {
+		// Seeding never leaves the state at zero, so two bits eventually differ
+		loop {
+			xoshiro256pp(&mut self.state);
+
+			let a = (self.state[0] & 1) == 1;
+			let b = (self.state[2] & 1) == 1;
+
+			if a != b {
+				return a;
+			}
+		}
+	}
+
+	/// Will generate a pair of independent normally distributed [`f64`]s from a single pair of uniforms
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut rng = Rng::from_seed(3);
+	/// let (a, b) = rng.normal_pair(10.0, 2.0);
+	///
+	/// println!("a = {a}, b = {b}");
+	/// ```
+	pub fn normal_pair(&mut self, mean: f64, std_dev: f64) -> (f64, f64) {
+		let (a, b) = standard_normal_pair_from(self);
+
+		(mean + std_dev * a, mean + std_dev * b)
+	}
+
+	/// Will generate a normally distributed [`f64`]
+	///
+	/// Box–Muller produces samples in pairs, so every other call is served from a cached spare.
+	/// `u1` is drawn from `(0, 1)`, so `ln(u1)` and the result are always finite
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::Rng;
+	///
+	/// let mut rng = Rng::from_seed(3);
+	/// let r = rng.normal(0.0, 1.0);
+	///
+	/// println!("r = {r}");
+	/// ```
+	pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+		let z = match self.spare.take() {
+			Some(z) => z,
+			None => {
+				let (a, b) = standard_normal_pair_from(self);
+				self.spare = Some(b);
+				a
+			}
+		};
+
+		mean + std_dev * z
+	}
+}
+
+/// A fluent way to configure an [`Rng`]: seed, stream and output variant
+///
+/// Defaults to a fresh seed drawn from the entropy-seeded global generator, stream `0` and [`Xoshiro::PlusPlus`].
+/// A builder with a seed set builds the same generator as [`Rng::with_stream`].
+///
+/// # Example
+/// ```
+/// use hel_random::{Rng, RngBuilder, Xoshiro};
+///
+/// let mut rng = RngBuilder::new().seed(42).stream(7).build();
+/// assert_eq!(rng.next_u64(), Rng::with_stream(42, 7).next_u64());
+///
+/// let mut star = RngBuilder::new().seed(42).variant(Xoshiro::StarStar).build();
+/// println!("r = {}", star.next_u64());
+///
+/// let mut fresh = RngBuilder::new().build();
+/// println!("r = {}", fresh.next_u64());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RngBuilder {
+	seed: Option<u64>,
+	stream: u64,
+	variant: Xoshiro,
+}
+
+impl RngBuilder {
+	/// Will create a builder with the defaults
+	pub const fn new() -> Self {
+		Self {
+			seed: None,
+			stream: 0,
+			variant: Xoshiro::PlusPlus,
+		}
+	}
+
+	/// Will seed the generator with `seed` instead of a fresh one
+	pub const fn seed(mut self, seed: u64) -> Self {
+		self.seed = Some(seed);
+		self
+	}
+
+	/// Will pick stream `stream_id` of the seed, see [`Rng::with_stream`]
+	pub const fn stream(mut self, stream_id: u64) -> Self {
+		self.stream = stream_id;
+		self
+	}
+
+	/// Will pick the output function
+	pub const fn variant(mut self, variant: Xoshiro) -> Self {
+		self.variant = variant;
+		self
+	}
+
+	/// Will build the configured generator
+	pub fn build(self) -> Rng {
+		let seed = self.seed.unwrap_or_else(entropy_seed);
+
+		Rng {
+			variant: self.variant,
+			..Rng::with_stream(seed, self.stream)
+		}
+	}
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn entropy_seed() -> u64 {
+	crate::u64()
+}
+
+// The global generator isn't there, the same fallback it would've used
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn entropy_seed() -> u64 {
+	let mut state = [0; 4];
+	crate::fallback_seed(&mut state);
+
+	state[0]
+}
+
+/// A factory of reproducible generators keyed by coordinates or ids
+///
+/// Every key gets its own [`Rng`] seeded with `mix64(key ^ seed)`, so procedural content
+/// (tiles, chunks, entities) gets the same randomness every time without storing any state per key.
+///
+/// # Example
+/// ```
+/// use hel_random::KeyedRng;
+///
+/// let world = KeyedRng::new(1234);
+///
+/// let tree = world.at_xy(-3, 17).next_u64() % 10 == 0;
+/// assert_eq!(tree, world.at_xy(-3, 17).next_u64() % 10 == 0);
+///
+/// assert_ne!(world.at(1).next_u64(), world.at(2).next_u64());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyedRng {
+	seed: u64,
+}
+
+impl KeyedRng {
+	/// Will create a factory for the world seeded with `seed`
+	pub const fn new(seed: u64) -> Self {
+		Self { seed }
+	}
+
+	/// Will return the seed the factory was created with
+	pub const fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	/// Will return a fresh generator for `key`, the same key always gives the same sequence
+	#[inline]
+	pub fn at(&self, key: u64) -> Rng {
+		Rng::from_seed(mix64(key ^ self.seed))
+	}
+
+	/// Will return a fresh generator for the grid cell `(x, y)`
+	///
+	/// The coordinates are packed into one key as `x << 32 | y`, so every cell gets a distinct key
+	#[inline]
+	pub fn at_xy(&self, x: i32, y: i32) -> Rng {
+		self.at((x as u32 as u64) << 32 | y as u32 as u64)
+	}
+}
+
+impl Source for Rng {
+	#[inline(always)]
+	fn next_u64(&mut self) -> u64 {
+		Rng::next_u64(self)
+	}
+
+	#[inline(always)]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+		Rng::normal(self, mean, std_dev)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reference_vectors() {
+		// Outputs of the reference C implementation for the state `[1, 2, 3, 4]`
+		let mut rng = Rng::from_seed(0);
+		rng.state = [1, 2, 3, 4];
+
+		let expected: [u64; 10] = [
+			41943041,
+			58720359,
+			3588806011781223,
+			3591011842654386,
+			9228616714210784205,
+			9973669472204895162,
+			14011001112246962877,
+			12406186145184390807,
+			15849039046786891736,
+			10450023813501588000,
+		];
+
+		for e in expected {
+			assert_eq!(rng.next_u64(), e);
+		}
+
+		// The reference SplitMix64 fills the state, seeded with 0
+		let mut rng = Rng::from_seed(0);
+		assert_eq!(rng.next_u64(), 0x53175d61490b23df);
+		assert_eq!(rng.next_u64(), 0x61da6f3dc380d507);
+		assert_eq!(rng.next_u64(), 0x5c0fdf91ec9a7bfc);
+	}
+
+	#[test]
+	fn starstar_reference() {
+		// Outputs of the reference C implementation of xoshiro256** for the state `[1, 2, 3, 4]`
+		let mut rng = RngBuilder::new().variant(Xoshiro::StarStar).build();
+		rng.state = [1, 2, 3, 4];
+
+		for e in [11520, 0, 1509978240, 1215971899390074240] {
+			assert_eq!(rng.next_u64(), e);
+		}
+
+		rng.reseed(3);
+		assert_eq!(rng.variant, Xoshiro::StarStar);
+	}
+
+	#[test]
+	fn builder_defaults() {
+		let mut built = RngBuilder::new().seed(8).build();
+		assert_eq!(built.next_u64(), Rng::from_seed(8).next_u64());
+
+		let mut a = RngBuilder::new().build();
+		let mut b = RngBuilder::new().build();
+		assert_ne!(a.next_u64(), b.next_u64());
+
+		assert_eq!(RngBuilder::new(), RngBuilder::default());
+	}
+
+	#[test]
+	fn jump_reference() {
+		let mut rng = Rng::from_seed(0);
+		rng.state = [1, 2, 3, 4];
+		rng.jump();
+
+		assert_eq!(rng.next_u64(), 0xec879073673df437);
+		assert_eq!(rng.next_u64(), 0x20d212a39aca1eaa);
+		assert_eq!(rng.next_u64(), 0xc19d712a27e40f57);
+	}
+
+	#[test]
+	fn keyed_cells() {
+		let world = KeyedRng::new(99);
+		let other = KeyedRng::new(100);
+
+		assert_eq!(
+			world.at(5).next_u64(),
+			Rng::from_seed(mix64(5 ^ 99)).next_u64()
+		);
+		assert_ne!(world.at(5).next_u64(), other.at(5).next_u64());
+
+		let mut firsts: Vec<u64> = (-4..4)
+			.flat_map(|x| (-4..4).map(move |y| (x, y)))
+			.map(|(x, y)| world.at_xy(x, y).next_u64())
+			.collect();
+		firsts.sort();
+		firsts.dedup();
+		assert_eq!(firsts.len(), 64);
+	}
+
+	#[test]
+	fn reseed_clears_caches() {
+		let mut rng = Rng::from_seed(3);
+		rng.normal(0.0, 1.0);
+		rng.take_bits(5);
+
+		rng.reseed(11);
+
+		let mut fresh = Rng::from_seed(11);
+		assert_eq!(rng.normal(0.0, 1.0), fresh.normal(0.0, 1.0));
+		assert_eq!(rng.normal(0.0, 1.0), fresh.normal(0.0, 1.0));
+		assert_eq!(rng.take_bits(7), fresh.take_bits(7));
+		assert_eq!(rng.next_u64(), fresh.next_u64());
+	}
+
+	#[test]
+	fn same_seed_same_sequence() {
+		let mut a = Rng::from_seed(0xdead_beef);
+		let mut b = Rng::from_seed(0xdead_beef);
+		let mut c = Rng::from_seed(0xdead_bee0);
+
+		for _ in 0..1_000 {
+			let x = a.next_u64();
+			assert_eq!(x, b.next_u64());
+			assert_ne!(x, c.next_u64());
+		}
+	}
+
+	#[test]
+	fn streams_differ() {
+		let mut streams: Vec<Rng> = (0..64).map(|id| Rng::with_stream(1, id)).collect();
+		let mut firsts: Vec<u64> = streams.iter_mut().map(Rng::next_u64).collect();
+
+		firsts.sort();
+		firsts.dedup();
+		assert_eq!(firsts.len(), 64);
+
+		let mut a = Rng::with_stream(9, 3);
+		let mut b = Rng::with_stream(9, 3);
+		assert_eq!(a.next_u64(), b.next_u64());
+
+		// Seed and stream aren't just xored together
+		for (seed, id) in [(0, 1), (1, 2), (0xdead_beef, 77), (u64::MAX, u64::MAX)] {
+			let mut stream = Rng::with_stream(seed, id);
+			let mut xored = Rng::from_seed(seed ^ mix64(id));
+
+			assert_ne!(stream.next_u64(), xored.next_u64());
+		}
+
+		assert_eq!(stream_gamma(0), 0x9e3779b97f4a7c15);
+		assert!((0..1_000).all(|id| stream_gamma(id) & 1 == 1));
+	}
+
+	#[test]
+	fn seeded_source_surface() {
+		let run = || {
+			let mut rng = Rng::from_seed(21);
+			let mut v: Vec<u32> = (0..20).collect();
+			rng.shuffle(&mut v);
+
+			(
+				v,
+				rng.generate::<u128>(),
+				rng.generate::<char>(),
+				rng.below(1000),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	fn generic_normal_keeps_the_spare() {
+		fn draw<S: Source>(source: &mut S) -> f64 {
+			source.normal(0.0, 1.0)
+		}
+
+		let mut a = Rng::from_seed(6);
+		let mut b = a.clone();
+
+		for _ in 0..5 {
+			assert_eq!(draw(&mut a), b.normal(0.0, 1.0));
+		}
+		assert_eq!(a.next_u64(), b.next_u64());
+
+		// A single Box–Muller implementation behind both
+		let pair = Rng::from_seed(2).normal_pair(0.0, 1.0);
+		assert_eq!(pair, standard_normal_pair_from(&mut Rng::from_seed(2)));
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = Rng::from_seed(34);
+			let options = [1, 2, 3, 4, 5];
+
+			let r = rng.range(10, 20);
+			assert!((10..20).contains(&r));
+			assert!(rng.try_range(3, 3).is_err());
+
+			let (i, &v) = rng.choose_indexed(&options).unwrap();
+			assert_eq!(options[i], v);
+
+			let f = rng.range_f64(-1.0, 1.0);
+			assert!((-1.0..1.0).contains(&f));
+
+			let l = rng.loguniform(1e-3, 1e3);
+			assert!((1e-3..1e3).contains(&l));
+
+			let e = rng.exponential(2.0);
+			assert!(e > 0.0);
+
+			(r, i, f, l, e, rng.geometric(0.5), rng.normal(0.0, 1.0))
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	fn iter_matches_generate() {
+		let mut a = Rng::from_seed(4);
+		let mut b = a.clone();
+
+		let drawn: Vec<u16> = a.iter().take(10).collect();
+		let expected: Vec<u16> = (0..10).map(|_| b.generate()).collect();
+		assert_eq!(drawn, expected);
+
+		// The iterator borrows the generator, it continues where it left off
+		assert_eq!(a.next_u64(), b.next_u64());
+	}
+
+	#[test]
+	fn fork_children() {
+		let mut parent = Rng::from_seed(77);
+		let mut reference = parent.clone();
+
+		let children: [Rng; 8] = parent.fork();
+
+		let mut firsts: Vec<u64> = children.into_iter().map(|mut c| c.next_u64()).collect();
+		firsts.sort();
+		firsts.dedup();
+		assert_eq!(firsts.len(), 8);
+
+		// The parent skipped exactly the 8 seeds
+		for _ in 0..8 {
+			reference.next_u64();
+		}
+		assert_eq!(parent.next_u64(), reference.next_u64());
+
+		let none: [Rng; 0] = parent.fork();
+		assert!(none.is_empty());
+	}
+
+	#[test]
+	fn take_bits_is_a_bit_stream() {
+		let mut a = Rng::from_seed(13);
+		let mut b = a.clone();
+
+		let words = [b.next_u64(), b.next_u64(), b.next_u64()];
+
+		// 3 + 5 + 56 bits make exactly one word, then 64 more, then split 1 + 63
+		let mut expected = 0;
+		expected |= a.take_bits(3);
+		expected |= a.take_bits(5) << 3;
+		expected |= a.take_bits(56) << 8;
+		assert_eq!(expected, words[0]);
+
+		assert_eq!(a.take_bits(64), words[1]);
+
+		let low = a.take_bits(1);
+		let high = a.take_bits(63);
+		assert_eq!(low | (high << 1), words[2]);
+		assert_eq!(a.bits_len, 0);
+
+		// Straddling two draws
+		let mut a = Rng::from_seed(13);
+		a.take_bits(60);
+		let straddle = a.take_bits(8);
+		assert_eq!(straddle, (words[0] >> 60) | ((words[1] & 0xf) << 4));
+	}
+
+	#[test]
+	#[should_panic]
+	fn take_zero_bits() {
+		Rng::from_seed(0).take_bits(0);
+	}
+
+	#[test]
+	fn normal_uses_spare() {
+		let mut a = Rng::from_seed(7);
+		let mut b = a.clone();
+
+		let (x, y) = a.normal_pair(0.0, 1.0);
+
+		assert_eq!(b.normal(0.0, 1.0), x);
+		assert!(b.spare.is_some());
+		assert_eq!(b.normal(0.0, 1.0), y);
+		assert!(b.spare.is_none());
+
+		// Both generators consumed the same pair of uniforms
+		assert_eq!(a.next_u64(), b.next_u64());
+	}
+
+	#[test]
+	fn normal_moments() {
+		const TRIES: usize = 100_000;
+
+		let mut rng = Rng::from_seed(11);
+		let samples: Vec<f64> = (0..TRIES).map(|_| rng.normal(5.0, 2.0)).collect();
+
+		let mean = samples.iter().sum::<f64>() / TRIES as f64;
+		let var = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / TRIES as f64;
+
+		println!("Mean: {mean}, variance: {var}");
+		assert!((mean - 5.0).abs() < 0.05);
+		assert!((var - 4.0).abs() < 0.1);
+		assert!(samples.iter().all(|s| s.is_finite()));
+	}
+}
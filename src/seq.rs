@@ -0,0 +1,668 @@
+use std::ops::Range;
+
+use crate::{Global, Rng, Source};
+
+/// Will return a reference to a uniformly chosen element, or [`None`] if `options` is empty
+///
+/// # Example
+/// ```
+/// use hel_random::choose;
+///
+/// let fruits = ["apple", "banana", "cherry"];
+/// let fruit = choose(&fruits).unwrap();
+///
+/// assert!(fruits.contains(fruit));
+/// assert!(choose::<u8>(&[]).is_none());
+/// ```
+#[inline]
+pub fn choose<T>(options: &[T]) -> Option<&T> {
+	Global.choose(options)
+}
+
+/// Will return a copy of a uniformly chosen element, or [`None`] if `options` is empty
+///
+/// Handy for picking a random variant of a C-like enum
+///
+/// # Example
+/// ```
+/// use hel_random::choose_copy;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// let color = choose_copy(&[Color::Red, Color::Green, Color::Blue]).unwrap();
+/// println!("color = {color:?}");
+/// ```
+#[inline]
+pub fn choose_copy<T: Copy>(options: &[T]) -> Option<T> {
+	Global.choose_copy(options)
+}
+
+/// Will return a uniformly chosen element along with its index, or [`None`] if `options` is empty
+///
+/// Saves searching for the position when the pick is to be removed or replaced afterwards
+///
+/// # Example
+/// ```
+/// use hel_random::choose_indexed;
+///
+/// let mut queue = vec!["a", "b", "c"];
+/// let (i, &job) = choose_indexed(&queue).unwrap();
+///
+/// assert_eq!(queue.swap_remove(i), job);
+/// assert!(choose_indexed::<u8>(&[]).is_none());
+/// ```
+#[inline]
+pub fn choose_indexed<T>(options: &[T]) -> Option<(usize, &T)> {
+	Global.choose_indexed(options)
+}
+
+/// Will return a uniformly chosen element of a non-empty array
+///
+/// An empty array is rejected at compile time
+///
+/// # Example
+/// ```
+/// use hel_random::choose_array;
+///
+/// let side = choose_array(["heads", "tails"]);
+/// println!("side = {side}");
+/// ```
+///
+/// ```compile_fail
+/// let nothing: u8 = hel_random::choose_array([]);
+/// ```
+#[inline]
+pub fn choose_array<T: Copy, const N: usize>(arr: [T; N]) -> T {
+	choose_array_from(&mut Global, arr)
+}
+
+pub(crate) fn choose_array_from<S: Source, T: Copy, const N: usize>(
+	source: &mut S,
+	arr: [T; N],
+) -> T {
+	const { assert!(N > 0, "cannot choose from an empty array") };
+
+	arr[source.below(N as u64) as usize]
+}
+
+/// Will return one of the two arguments, each with probability `1/2`
+///
+/// # Example
+/// ```
+/// use hel_random::pick2;
+///
+/// let turn = pick2("left", "right");
+///
+/// assert!(turn == "left" || turn == "right");
+/// ```
+#[inline]
+pub fn pick2<T>(a: T, b: T) -> T {
+	pick2_from(&mut Global, a, b)
+}
+
+pub(crate) fn pick2_from<S: Source, T>(source: &mut S, a: T, b: T) -> T {
+	match source.below(2) {
+		0 => a,
+		_ => b,
+	}
+}
+
+/// Will return one of the three arguments, each with probability `1/3`
+///
+/// # Example
+/// ```
+/// use hel_random::pick3;
+///
+/// let hand = pick3("rock", "paper", "scissors");
+///
+/// println!("hand = {hand}");
+/// ```
+#[inline]
+pub fn pick3<T>(a: T, b: T, c: T) -> T {
+	pick3_from(&mut Global, a, b, c)
+}
+
+pub(crate) fn pick3_from<S: Source, T>(source: &mut S, a: T, b: T, c: T) -> T {
+	match source.below(3) {
+		0 => a,
+		1 => b,
+		_ => c,
+	}
+}
+
+/// Will return a uniformly chosen contiguous sub-slice of exactly `len` elements,
+/// or [`None`] if `len` exceeds the length of `slice`
+///
+/// # Example
+/// ```
+/// use hel_random::random_window;
+///
+/// let samples = [1, 2, 3, 4, 5, 6, 7, 8];
+/// let window = random_window(&samples, 3).unwrap();
+///
+/// assert_eq!(window.len(), 3);
+/// assert!(samples.windows(3).any(|w| w == window));
+/// assert!(random_window(&samples, 9).is_none());
+/// ```
+#[inline]
+pub fn random_window<T>(slice: &[T], len: usize) -> Option<&[T]> {
+	random_window_from(&mut Global, slice, len)
+}
+
+pub(crate) fn random_window_from<'a, S: Source, T>(
+	source: &mut S,
+	slice: &'a [T],
+	len: usize,
+) -> Option<&'a [T]> {
+	let starts = slice.len().checked_sub(len)? + 1;
+	let start = source.below(starts as u64) as usize;
+
+	Some(&slice[start..start + len])
+}
+
+/// Will shuffle `slice` in place, every permutation is equally likely (Fisher–Yates)
+///
+/// # Example
+/// ```
+/// use hel_random::shuffle;
+///
+/// let mut deck: Vec<u32> = (0..52).collect();
+/// shuffle(&mut deck);
+///
+/// deck.sort();
+/// assert_eq!(deck, (0..52).collect::<Vec<_>>());
+/// ```
+pub fn shuffle<T>(slice: &mut [T]) {
+	Global.shuffle(slice)
+}
+
+/// Will shuffle only `slice[range]` in place, leaving the elements outside of it untouched
+///
+/// # Panics
+/// If `range` is out of bounds or its start is past its end
+///
+/// # Example
+/// ```
+/// use hel_random::shuffle_range;
+///
+/// let mut rows = ["header", "a", "b", "c", "footer"];
+/// shuffle_range(&mut rows, 1..4);
+///
+/// assert_eq!(rows[0], "header");
+/// assert_eq!(rows[4], "footer");
+/// ```
+pub fn shuffle_range<T>(slice: &mut [T], range: Range<usize>) {
+	shuffle_range_from(&mut Global, slice, range)
+}
+
+pub(crate) fn shuffle_range_from<S: Source, T>(
+	source: &mut S,
+	slice: &mut [T],
+	range: Range<usize>,
+) {
+	let len = slice.len();
+
+	match slice.get_mut(range.clone()) {
+		Some(sub) => source.shuffle(sub),
+		None => panic!("range {range:?} is out of bounds for a slice of length {len}"),
+	}
+}
+
+/// Will shuffle the order of consecutive `group_size` chunks of `slice`, keeping the order within each chunk
+///
+/// A shorter tail chunk is shuffled along as a unit too. Every order of the chunks is equally likely.
+///
+/// # Panics
+/// If `group_size == 0`
+///
+/// # Example
+/// ```
+/// use hel_random::shuffle_groups;
+///
+/// let mut steps = [1, 2, 10, 20, 100];
+/// shuffle_groups(&mut steps, 2);
+///
+/// // `[1, 2]`, `[10, 20]` and `[100]` stay together
+/// let at = steps.iter().position(|&s| s == 10).unwrap();
+/// assert_eq!(steps[at + 1], 20);
+/// ```
+pub fn shuffle_groups<T>(slice: &mut [T], group_size: usize) {
+	shuffle_groups_from(&mut Global, slice, group_size)
+}
+
+pub(crate) fn shuffle_groups_from<S: Source, T>(
+	source: &mut S,
+	slice: &mut [T],
+	group_size: usize,
+) {
+	assert!(group_size > 0, "groups can't be empty");
+
+	let len = slice.len();
+	let mut order: Vec<usize> = (0..len.div_ceil(group_size)).collect();
+	source.shuffle(&mut order);
+
+	// Where every position takes its element from
+	let src: Vec<usize> = order
+		.iter()
+		.flat_map(|&g| g * group_size..((g + 1) * group_size).min(len))
+		.collect();
+
+	// Applying the permutation cycle by cycle needs only swaps
+	let mut done = vec![false; len];
+
+	for start in 0..len {
+		let mut i = start;
+
+		while !done[i] {
+			done[i] = true;
+
+			if src[i] == start {
+				break;
+			}
+
+			slice.swap(i, src[i]);
+			i = src[i];
+		}
+	}
+}
+
+/// Will shuffle a copy of `deck` and deal `hands` hands of `per_hand` cards from the top
+///
+/// Cards left over after dealing are dropped, `deck` itself is untouched
+///
+/// # Panics
+/// If `hands * per_hand` exceeds the number of cards in `deck`
+///
+/// # Example
+/// ```
+/// use hel_random::deal;
+///
+/// let deck: Vec<u8> = (0..52).collect();
+/// let hands = deal(&deck, 4, 5);
+///
+/// assert_eq!(hands.len(), 4);
+/// assert!(hands.iter().all(|hand| hand.len() == 5));
+/// ```
+pub fn deal<T: Clone>(deck: &[T], hands: usize, per_hand: usize) -> Vec<Vec<T>> {
+	deal_from(&mut Global, deck, hands, per_hand)
+}
+
+pub(crate) fn deal_from<S: Source, T: Clone>(
+	source: &mut S,
+	deck: &[T],
+	hands: usize,
+	per_hand: usize,
+) -> Vec<Vec<T>> {
+	let needed = hands.checked_mul(per_hand);
+	assert!(
+		needed.is_some_and(|n| n <= deck.len()),
+		"can't deal {hands} hands of {per_hand} from {} cards",
+		deck.len()
+	);
+
+	let mut cards = deck.to_vec();
+	source.shuffle(&mut cards);
+
+	let mut cards = cards.into_iter();
+
+	(0..hands)
+		.map(|_| cards.by_ref().take(per_hand).collect())
+		.collect()
+}
+
+/// Will shuffle `slice` with a temporary [`Rng`] seeded from `seed`, leaving the global state untouched
+///
+/// The same seed always shuffles the same way
+///
+/// # Example
+/// ```
+/// use hel_random::seeded_shuffle;
+///
+/// let mut a: Vec<u32> = (0..10).collect();
+/// let mut b = a.clone();
+///
+/// seeded_shuffle(7, &mut a);
+/// seeded_shuffle(7, &mut b);
+///
+/// assert_eq!(a, b);
+/// ```
+#[inline]
+pub fn seeded_shuffle<T>(seed: u64, slice: &mut [T]) {
+	Rng::from_seed(seed).shuffle(slice);
+}
+
+/// Will return the minimum of `items`, picking uniformly among the elements that tie for it,
+/// or [`None`] if `items` is empty
+///
+/// A plain [`Iterator::min`] always favors the first of equal elements, which skews schedulers and
+/// load balancers. Ties are resolved in a single pass with reservoir sampling.
+///
+/// # Example
+/// ```
+/// use hel_random::min_random_tiebreak;
+///
+/// let loads = [3, 1, 4, 1, 5];
+///
+/// assert_eq!(min_random_tiebreak(&loads), Some(1));
+/// assert_eq!(min_random_tiebreak::<u8>(&[]), None);
+/// ```
+pub fn min_random_tiebreak<T: Ord + Clone>(items: &[T]) -> Option<T> {
+	min_random_tiebreak_from(&mut Global, items)
+}
+
+pub(crate) fn min_random_tiebreak_from<S: Source, T: Ord + Clone>(
+	source: &mut S,
+	items: &[T],
+) -> Option<T> {
+	let mut min = items.first()?;
+	let mut ties = 1;
+
+	for item in &items[1..] {
+		match item.cmp(min) {
+			std::cmp::Ordering::Less => {
+				min = item;
+				ties = 1;
+			}
+			std::cmp::Ordering::Equal => {
+				ties += 1;
+
+				// Every tie seen so far is kept with probability `1 / ties`
+				if source.below(ties) == 0 {
+					min = item;
+				}
+			}
+			std::cmp::Ordering::Greater => {}
+		}
+	}
+
+	Some(min.clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn choose_all_reachable() {
+		let options = [0usize, 1, 2, 3, 4];
+		let mut seen = [false; 5];
+
+		for _ in 0..1_000 {
+			seen[choose_copy(&options).unwrap()] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(choose_copy(&[42]), Some(42));
+		assert_eq!(choose_copy::<u8>(&[]), None);
+	}
+
+	#[test]
+	fn choose_indexed_matches() {
+		let options = [10, 11, 12, 13];
+		let mut seen = [false; 4];
+
+		for _ in 0..1_000 {
+			let (i, &v) = choose_indexed(&options).unwrap();
+			assert_eq!(v, options[i]);
+
+			seen[i] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(choose_indexed(&[7]), Some((0, &7)));
+	}
+
+	#[test]
+	fn choose_array_all_reachable() {
+		let mut seen = [false; 4];
+
+		for _ in 0..1_000 {
+			seen[choose_array([0, 1, 2, 3])] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(choose_array([7]), 7);
+	}
+
+	#[test]
+	fn deal_hands() {
+		let deck: Vec<u32> = (0..52).collect();
+		let hands = deal(&deck, 4, 13);
+
+		let mut dealt: Vec<u32> = hands.iter().flatten().copied().collect();
+		assert!(hands.iter().all(|hand| hand.len() == 13));
+
+		dealt.sort();
+		assert_eq!(dealt, deck);
+
+		assert_eq!(deal(&deck, 3, 0), vec![Vec::<u32>::new(); 3]);
+		assert!(deal(&deck, 0, 100).is_empty());
+		assert_eq!(deal(&[1], 1, 1), [[1]]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn deal_too_many() {
+		deal(&[1, 2, 3], 2, 2);
+	}
+
+	#[test]
+	fn seeded_shuffle_is_reproducible() {
+		let original: Vec<u32> = (0..50).collect();
+
+		let mut a = original.clone();
+		let mut b = original.clone();
+		let mut c = original.clone();
+
+		seeded_shuffle(1, &mut a);
+		seeded_shuffle(1, &mut b);
+		seeded_shuffle(2, &mut c);
+
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+		assert_ne!(a, original);
+	}
+
+	#[test]
+	fn shuffle_permutes() {
+		let mut v: Vec<u32> = (0..100).collect();
+		shuffle(&mut v);
+		assert_ne!(v, (0..100).collect::<Vec<_>>());
+
+		v.sort();
+		assert_eq!(v, (0..100).collect::<Vec<_>>());
+
+		shuffle::<u8>(&mut []);
+		shuffle(&mut [1]);
+	}
+
+	#[test]
+	fn tiebreak_is_uniform() {
+		// Ordered by `load` alone, so the id tells ties apart
+		#[derive(Clone, Debug)]
+		struct Server {
+			load: u32,
+			id: usize,
+		}
+
+		impl PartialEq for Server {
+			fn eq(&self, other: &Self) -> bool {
+				self.load == other.load
+			}
+		}
+
+		impl Eq for Server {}
+
+		impl PartialOrd for Server {
+			fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+				Some(self.cmp(other))
+			}
+		}
+
+		impl Ord for Server {
+			fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+				self.load.cmp(&other.load)
+			}
+		}
+
+		const TRIES: usize = 30_000;
+
+		let servers: Vec<Server> = [5, 2, 7, 2, 2, 9]
+			.into_iter()
+			.enumerate()
+			.map(|(id, load)| Server { load, id })
+			.collect();
+
+		let mut picks = [0usize; 6];
+
+		for _ in 0..TRIES {
+			picks[min_random_tiebreak(&servers).unwrap().id] += 1;
+		}
+
+		println!("Picks: {picks:?}");
+		assert_eq!(picks[0] + picks[2] + picks[5], 0);
+
+		for i in [1, 3, 4] {
+			let freq = picks[i] as f64 / TRIES as f64;
+			assert!((freq - 1.0 / 3.0).abs() < 0.02);
+		}
+	}
+
+	#[test]
+	fn shuffle_range_keeps_outside() {
+		let original: Vec<u32> = (0..100).collect();
+		let mut v = original.clone();
+
+		shuffle_range(&mut v, 10..90);
+		assert_eq!(v[..10], original[..10]);
+		assert_eq!(v[90..], original[90..]);
+		assert_ne!(v[10..90], original[10..90]);
+
+		v[10..90].sort();
+		assert_eq!(v, original);
+
+		shuffle_range(&mut v, 100..100);
+		assert_eq!(v, original);
+	}
+
+	#[test]
+	#[should_panic]
+	fn shuffle_range_out_of_bounds() {
+		shuffle_range(&mut [1, 2, 3], 1..4);
+	}
+
+	#[test]
+	fn windows_all_reachable() {
+		let items = [0usize, 1, 2, 3, 4];
+		let mut seen = [false; 3];
+
+		for _ in 0..1_000 {
+			let window = random_window(&items, 3).unwrap();
+			assert_eq!(window, &items[window[0]..window[0] + 3]);
+			seen[window[0]] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(random_window(&items, 5), Some(&items[..]));
+		assert_eq!(random_window(&items, 0), Some(&[][..]));
+		assert_eq!(random_window::<u8>(&[], 0), Some(&[][..]));
+		assert!(random_window::<u8>(&[], 1).is_none());
+	}
+
+	#[test]
+	fn picks_are_uniform() {
+		const TRIES: usize = 30_000;
+
+		let mut twos = [0usize; 2];
+		let mut threes = [0usize; 3];
+
+		for _ in 0..TRIES {
+			twos[pick2(0, 1)] += 1;
+			threes[pick3(0, 1, 2)] += 1;
+		}
+
+		println!("Picks: {twos:?}, {threes:?}");
+		assert!(twos
+			.iter()
+			.all(|&c| (c as f64 / TRIES as f64 - 0.5).abs() < 0.02));
+		assert!(threes
+			.iter()
+			.all(|&c| (c as f64 / TRIES as f64 - 1.0 / 3.0).abs() < 0.02));
+	}
+
+	#[test]
+	fn groups_stay_together() {
+		let original: Vec<u32> = (0..11).collect();
+		let mut firsts = std::collections::HashSet::new();
+
+		for _ in 0..200 {
+			let mut v = original.clone();
+			shuffle_groups(&mut v, 3);
+
+			// Chunks of the result are the original chunks, the tail one possibly moved
+			let mut groups: Vec<Vec<u32>> = Vec::new();
+			let mut rest = &v[..];
+
+			while let Some(&first) = rest.first() {
+				let size = if first == 9 { 2 } else { 3 };
+				assert_eq!(first % 3, 0);
+				assert_eq!(
+					rest[..size],
+					original[first as usize..first as usize + size]
+				);
+
+				groups.push(rest[..size].to_vec());
+				rest = &rest[size..];
+			}
+
+			assert_eq!(groups.len(), 4);
+			firsts.insert(v[0]);
+		}
+
+		assert_eq!(firsts.len(), 4);
+
+		let mut v = original.clone();
+		shuffle_groups(&mut v, 11);
+		assert_eq!(v, original);
+		shuffle_groups::<u8>(&mut [], 3);
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = Rng::from_seed(8);
+			let deck: Vec<u32> = (0..20).collect();
+
+			let mut ranged = deck.clone();
+			rng.shuffle_range(&mut ranged, 5..15);
+
+			let mut grouped = deck.clone();
+			rng.shuffle_groups(&mut grouped, 3);
+
+			(
+				rng.choose_copy(&deck),
+				rng.choose_array([1, 2, 3, 4]),
+				rng.pick2('a', 'b'),
+				rng.pick3('a', 'b', 'c'),
+				rng.random_window(&deck, 4).map(<[u32]>::to_vec),
+				ranged,
+				grouped,
+				rng.deal(&deck, 3, 4),
+				rng.min_random_tiebreak(&[3, 1, 1, 2, 1]),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	#[should_panic]
+	fn groups_of_zero() {
+		shuffle_groups(&mut [1, 2], 0);
+	}
+}
@@ -0,0 +1,1179 @@
+use std::collections::HashSet;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+use std::net::{SocketAddrV4, SocketAddrV6};
+use std::ops::{Range, RangeInclusive};
+use std::time::Duration;
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+use std::time::SystemTime;
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+use crate::{Random, RangeInt};
+use crate::{RandomVariant, RangeError};
+
+/// A core generator for the ergonomic helpers to run on
+///
+/// Only [`Source::next_u64`] is required, everything else is built on top of it,
+/// so an alternative algorithm (PCG, ChaCha, ...) gets the whole surface by implementing one method.
+/// [`Rng`](crate::Rng) is the xoshiro256++ implementation, [`Global`] is the process-wide one.
+///
+/// # Example
+/// ```
+/// use hel_random::{mix64, Source};
+///
+/// // A (terrible) counter based generator
+/// struct Counter(u64);
+///
+/// impl Source for Counter {
+///     fn next_u64(&mut self) -> u64 {
+///         self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+///         mix64(self.0)
+///     }
+/// }
+///
+/// let mut source = Counter(0);
+///
+/// let mut deck = [1, 2, 3, 4];
+/// source.shuffle(&mut deck);
+///
+/// let r: u32 = source.generate();
+/// println!("deck = {deck:?}, r = {r}");
+/// ```
+pub trait Source {
+	/// Will generate a random [`u64`]
+	fn next_u64(&mut self) -> u64;
+
+	/// Will generate a random `T`
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn generate<T: Random>(&mut self) -> T
+	where
+		Self: Sized,
+	{
+		T::random_from(self)
+	}
+
+	/// Will generate a uniform [`u64`] in `[0, n)` without modulo bias (Lemire's method)
+	///
+	/// # Panics
+	/// If `n == 0`
+	#[inline]
+	fn below(&mut self, n: u64) -> u64 {
+		assert!(n > 0, "can't draw below 0");
+
+		let mut m = self.next_u64() as u128 * n as u128;
+
+		if (m as u64) < n {
+			let threshold = n.wrapping_neg() % n;
+
+			while (m as u64) < threshold {
+				m = self.next_u64() as u128 * n as u128;
+			}
+		}
+
+		(m >> 64) as u64
+	}
+
+	/// Will fill `buf` with random bytes, 8 bytes per draw in little-endian order
+	#[inline]
+	fn fill_bytes(&mut self, buf: &mut [u8]) {
+		for_each_byte(self, buf, |dst, src| *dst = src);
+	}
+
+	/// Will return a reference to a uniformly chosen element, or [`None`] if `options` is empty
+	#[inline]
+	fn choose<'a, T>(&mut self, options: &'a [T]) -> Option<&'a T>
+	where
+		Self: Sized,
+	{
+		match options.len() {
+			0 => None,
+			len => Some(&options[self.below(len as u64) as usize]),
+		}
+	}
+
+	/// Will shuffle `slice` in place, every permutation is equally likely (Fisher–Yates)
+	fn shuffle<T>(&mut self, slice: &mut [T])
+	where
+		Self: Sized,
+	{
+		for i in (1..slice.len()).rev() {
+			slice.swap(i, self.below(i as u64 + 1) as usize);
+		}
+	}
+
+	/// Will return a uniformly chosen element along with its index, or [`None`] if `options` is empty
+	#[inline]
+	fn choose_indexed<'a, T>(&mut self, options: &'a [T]) -> Option<(usize, &'a T)>
+	where
+		Self: Sized,
+	{
+		match options.len() {
+			0 => None,
+			len => {
+				let i = self.below(len as u64) as usize;
+
+				Some((i, &options[i]))
+			}
+		}
+	}
+
+	/// Will generate a uniform [`u64`] in `[low, high)`, or a [`RangeError`] if the bounds are invalid
+	#[inline]
+	fn try_range(&mut self, low: u64, high: u64) -> Result<u64, RangeError> {
+		match low.cmp(&high) {
+			std::cmp::Ordering::Less => Ok(low + self.below(high - low)),
+			std::cmp::Ordering::Equal => Err(RangeError::Empty { bound: low }),
+			std::cmp::Ordering::Greater => Err(RangeError::Inverted { low, high }),
+		}
+	}
+
+	/// Will generate a uniform [`u64`] in `[low, high)`
+	///
+	/// # Panics
+	/// If `low >= high`
+	#[inline]
+	fn range(&mut self, low: u64, high: u64) -> u64 {
+		match self.try_range(low, high) {
+			Ok(r) => r,
+			Err(e) => panic!("{e}"),
+		}
+	}
+
+	/// Will generate a uniform [`f64`] in `[low, high)`, see [`range_f64`](crate::range_f64)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn range_f64(&mut self, low: f64, high: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::range_f64_from(self, low, high)
+	}
+
+	/// Will generate an [`f64`] in `[low, high)` uniform on a log scale, see [`loguniform`](crate::loguniform)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn loguniform(&mut self, low: f64, high: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::loguniform_from(self, low, high)
+	}
+
+	/// Will generate a normally distributed [`f64`], see [`normal`](crate::normal)
+	///
+	/// This drops the second sample of every Box–Muller pair, [`Rng`](crate::Rng) overrides it
+	/// with [`Rng::normal`](crate::Rng::normal), which keeps the spare for the next call
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn normal(&mut self, mean: f64, std_dev: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::normal_from(self, mean, std_dev)
+	}
+
+	/// Will generate an exponentially distributed [`f64`] with rate `lambda`, see [`exponential`](crate::exponential)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn exponential(&mut self, lambda: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::exponential_from(self, lambda)
+	}
+
+	/// Will generate the number of failures before the first success, see [`geometric`](crate::geometric)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn geometric(&mut self, p: f64) -> u64
+	where
+		Self: Sized,
+	{
+		crate::distributions::geometric_from(self, p)
+	}
+
+	/// Will return a copy of a uniformly chosen element, or [`None`] if `options` is empty
+	#[inline]
+	fn choose_copy<T: Copy>(&mut self, options: &[T]) -> Option<T>
+	where
+		Self: Sized,
+	{
+		self.choose(options).copied()
+	}
+
+	/// Will return a uniformly chosen element of a non-empty array, see [`choose_array`](crate::choose_array)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn choose_array<T: Copy, const N: usize>(&mut self, arr: [T; N]) -> T
+	where
+		Self: Sized,
+	{
+		crate::seq::choose_array_from(self, arr)
+	}
+
+	/// Will return one of the two arguments, each with probability `1/2`
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn pick2<T>(&mut self, a: T, b: T) -> T
+	where
+		Self: Sized,
+	{
+		crate::seq::pick2_from(self, a, b)
+	}
+
+	/// Will return one of the three arguments, each with probability `1/3`
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn pick3<T>(&mut self, a: T, b: T, c: T) -> T
+	where
+		Self: Sized,
+	{
+		crate::seq::pick3_from(self, a, b, c)
+	}
+
+	/// Will return a uniformly chosen sub-slice of `len` elements, see [`random_window`](crate::random_window)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_window<'a, T>(&mut self, slice: &'a [T], len: usize) -> Option<&'a [T]>
+	where
+		Self: Sized,
+	{
+		crate::seq::random_window_from(self, slice, len)
+	}
+
+	/// Will shuffle only `slice[range]` in place, see [`shuffle_range`](crate::shuffle_range)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn shuffle_range<T>(&mut self, slice: &mut [T], range: std::ops::Range<usize>)
+	where
+		Self: Sized,
+	{
+		crate::seq::shuffle_range_from(self, slice, range)
+	}
+
+	/// Will shuffle the order of `group_size` chunks of `slice`, see [`shuffle_groups`](crate::shuffle_groups)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn shuffle_groups<T>(&mut self, slice: &mut [T], group_size: usize)
+	where
+		Self: Sized,
+	{
+		crate::seq::shuffle_groups_from(self, slice, group_size)
+	}
+
+	/// Will deal `hands` hands of `per_hand` cards from a shuffled copy of `deck`, see [`deal`](crate::deal)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn deal<T: Clone>(&mut self, deck: &[T], hands: usize, per_hand: usize) -> Vec<Vec<T>>
+	where
+		Self: Sized,
+	{
+		crate::seq::deal_from(self, deck, hands, per_hand)
+	}
+
+	/// Will return the minimum of `items`, ties broken uniformly, see [`min_random_tiebreak`](crate::min_random_tiebreak)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn min_random_tiebreak<T: Ord + Clone>(&mut self, items: &[T]) -> Option<T>
+	where
+		Self: Sized,
+	{
+		crate::seq::min_random_tiebreak_from(self, items)
+	}
+
+	/// Will return a bucket index drawn from a cumulative distribution function, see [`sample_cdf`](crate::sample_cdf)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn sample_cdf(&mut self, cdf: &[f64]) -> usize
+	where
+		Self: Sized,
+	{
+		crate::weighted::sample_cdf_from(self, cdf)
+	}
+
+	/// Will draw `k` distinct indices by weight, see [`weighted_sample_without_replacement`](crate::weighted_sample_without_replacement)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn weighted_sample_without_replacement(&mut self, weights: &[f64], k: usize) -> Vec<usize>
+	where
+		Self: Sized,
+	{
+		crate::weighted::weighted_sample_without_replacement_from(self, weights, k)
+	}
+
+	/// Will return a clone of a label picked by weight, see [`sample_labeled`](crate::sample_labeled)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn sample_labeled<T: Clone>(&mut self, table: &[(T, f64)]) -> Option<T>
+	where
+		Self: Sized,
+	{
+		crate::weighted::sample_labeled_from(self, table)
+	}
+
+	/// Will return a bucket index picked by its integer count, see [`weighted_index_int`](crate::weighted_index_int)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn weighted_index_int(&mut self, weights: &[u64]) -> Option<usize>
+	where
+		Self: Sized,
+	{
+		crate::weighted::weighted_index_int_from(self, weights)
+	}
+
+	/// Will generate a [`Vec`] of random `T`s with a length drawn from `len_range`, see [`random_vec`](crate::random_vec)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_vec<T: Random>(&mut self, len_range: std::ops::Range<usize>) -> Vec<T>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_vec_from(self, len_range)
+	}
+
+	/// Will generate `len` random `T`s in ascending order, see [`sorted_vec`](crate::sorted_vec)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn sorted_vec<T: Random + Ord>(&mut self, len: usize) -> Vec<T>
+	where
+		Self: Sized,
+	{
+		crate::collections::sorted_vec_from(self, len)
+	}
+
+	/// Will generate up to `len` random `T`s in strictly ascending order, see [`sorted_unique_vec`](crate::sorted_unique_vec)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn sorted_unique_vec<T: Random + Ord>(&mut self, len: usize) -> Vec<T>
+	where
+		Self: Sized,
+	{
+		crate::collections::sorted_unique_vec_from(self, len)
+	}
+
+	/// Will fill `out` round-robin from `streams` generators seeded off this one, see [`fill_interleaved`](crate::fill_interleaved)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn fill_interleaved<T: Random>(&mut self, out: &mut [T], streams: usize)
+	where
+		Self: Sized,
+	{
+		crate::collections::fill_interleaved_from(self, out, streams)
+	}
+
+	/// Will generate a [`HashMap`](std::collections::HashMap) of random entries, see [`random_hashmap`](crate::random_hashmap)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_hashmap<K: Random + std::hash::Hash + Eq, V: Random>(
+		&mut self,
+		len_range: std::ops::Range<usize>,
+	) -> std::collections::HashMap<K, V>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_hashmap_from(self, len_range)
+	}
+
+	/// Will generate a [`HashSet`](std::collections::HashSet) of up to `target_len` random `T`s, see [`random_set`](crate::random_set)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_set<T: Random + std::hash::Hash + Eq>(
+		&mut self,
+		target_len: usize,
+		max_tries: usize,
+	) -> std::collections::HashSet<T>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_set_from(self, target_len, max_tries)
+	}
+
+	/// Will fill `out` with mutually distinct random `T`s, see [`fill_unique`](crate::fill_unique)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn fill_unique<T: Random + Eq + std::hash::Hash>(
+		&mut self,
+		out: &mut [T],
+		max_tries: usize,
+	) -> bool
+	where
+		Self: Sized,
+	{
+		crate::collections::fill_unique_from(self, out, max_tries)
+	}
+
+	/// Will generate a mask of `n` booleans with exactly `k` of them `true`, see [`random_mask_with_k`](crate::random_mask_with_k)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_mask_with_k(&mut self, n: usize, k: usize) -> Vec<bool>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_mask_with_k_from(self, n, k)
+	}
+
+	/// Will generate the edge list of an Erdős–Rényi graph, see [`random_graph`](crate::random_graph)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_graph(&mut self, n: usize, edge_prob: f64) -> Vec<(usize, usize)>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_graph_from(self, n, edge_prob)
+	}
+
+	/// Will generate the edge list of a uniformly random labeled tree, see [`random_tree`](crate::random_tree)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_tree(&mut self, n: usize) -> Vec<(usize, usize)>
+	where
+		Self: Sized,
+	{
+		crate::collections::random_tree_from(self, n)
+	}
+
+	/// Will generate a uniform [`f64`] in `[low, high]`, both endpoints reachable, see [`range_f64_inclusive`](crate::range_f64_inclusive)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn range_f64_inclusive(&mut self, low: f64, high: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::range_f64_inclusive_from(self, low, high)
+	}
+
+	/// Will generate a random walk of `steps` positions, see [`random_walk`](crate::random_walk)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_walk(&mut self, steps: usize, step_size: f64) -> Vec<f64>
+	where
+		Self: Sized,
+	{
+		crate::distributions::walk_from(self, steps, step_size)
+	}
+
+	/// Will generate a uniform [`f64`] in `[low, high)` rounded to `decimals` places, see [`rounded_f64`](crate::rounded_f64)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn rounded_f64(&mut self, low: f64, high: f64, decimals: u32) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::rounded_f64_from(self, low, high, decimals)
+	}
+
+	/// Will generate a symmetric triangular [`f64`] around `center`, see [`around`](crate::around)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn around(&mut self, center: f64, spread: f64) -> f64
+	where
+		Self: Sized,
+	{
+		crate::distributions::around_from(self, center, spread)
+	}
+
+	/// Will generate `len` independent normally distributed [`f64`]s, see [`normal_vec`](crate::normal_vec)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn normal_vec(&mut self, mean: f64, std_dev: f64, len: usize) -> Vec<f64>
+	where
+		Self: Sized,
+	{
+		crate::distributions::normal_vec_from(self, mean, std_dev, len)
+	}
+
+	/// Will generate a uniform [`f64`] in the closed interval `[0, 1]`, see [`closed01`](crate::closed01)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn closed01(&mut self) -> f64 {
+		crate::distributions::unit_closed(self.next_u64())
+	}
+
+	/// Will generate a uniform [`f64`] in the open interval `(0, 1)`, see [`open01`](crate::open01)
+	#[inline]
+	fn open01(&mut self) -> f64 {
+		crate::unit_open(self.next_u64())
+	}
+
+	/// Will sample a continuous distribution given its inverse CDF, see [`inverse_transform`](crate::inverse_transform)
+	#[inline]
+	fn inverse_transform<F: Fn(f64) -> f64>(&mut self, inv_cdf: F) -> f64
+	where
+		Self: Sized,
+	{
+		inv_cdf(self.open01())
+	}
+
+	/// Will generate an index in `1..=n` following Zipf's law, see [`zipf`](crate::zipf)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn zipf(&mut self, n: usize, exponent: f64) -> usize
+	where
+		Self: Sized,
+	{
+		crate::ZipfDistribution::new(n, exponent).sample_from(self)
+	}
+
+	/// Will randomly upper or lowercase every ASCII letter of `s`, see [`random_case`](crate::random_case)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_case(&mut self, s: &str) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::random_case_from(self, s)
+	}
+
+	/// Will generate a valid UTF-8 byte sequence of at most `max_len` bytes, see [`random_utf8_bytes`](crate::random_utf8_bytes)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_utf8_bytes(&mut self, max_len: usize) -> Vec<u8>
+	where
+		Self: Sized,
+	{
+		crate::strings::random_utf8_bytes_from(self, max_len)
+	}
+
+	/// Will generate a valid UTF-16 sequence of at most `max_units` code units, see [`random_utf16`](crate::random_utf16)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_utf16(&mut self, max_units: usize) -> Vec<u16>
+	where
+		Self: Sized,
+	{
+		crate::strings::random_utf16_from(self, max_units)
+	}
+
+	/// Will generate a printable ASCII [`char`], see [`ascii_printable`](crate::ascii_printable)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_printable(&mut self) -> char
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_printable_from(self)
+	}
+
+	/// Will generate a [`String`] of `len` printable ASCII characters, see [`ascii_printable_string`](crate::ascii_printable_string)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_printable_string(&mut self, len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_printable_string_from(self, len)
+	}
+
+	/// Will generate a uniform [`char`] with a scalar value in `lo..=hi`, see [`char_from_u32_range`](crate::char_from_u32_range)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn char_from_u32_range(&mut self, lo: u32, hi: u32) -> Option<char>
+	where
+		Self: Sized,
+	{
+		crate::strings::char_from_u32_range_from(self, lo, hi)
+	}
+
+	/// Will generate a [`String`] of `len` characters drawn from a frequency table, see [`text_from_frequencies`](crate::text_from_frequencies)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn text_from_frequencies(&mut self, freqs: &[(char, f64)], len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::text_from_frequencies_from(self, freqs, len)
+	}
+
+	/// Will generate an ASCII lowercase letter [`char`], see [`ascii_lowercase`](crate::ascii_lowercase)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_lowercase(&mut self) -> char
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_lowercase_from(self)
+	}
+
+	/// Will generate a [`String`] of `len` ASCII lowercase letters, see [`ascii_lowercase_string`](crate::ascii_lowercase_string)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_lowercase_string(&mut self, len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_lowercase_string_from(self, len)
+	}
+
+	/// Will generate an ASCII uppercase letter [`char`], see [`ascii_uppercase`](crate::ascii_uppercase)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_uppercase(&mut self) -> char
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_uppercase_from(self)
+	}
+
+	/// Will generate a [`String`] of `len` ASCII uppercase letters, see [`ascii_uppercase_string`](crate::ascii_uppercase_string)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_uppercase_string(&mut self, len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_uppercase_string_from(self, len)
+	}
+
+	/// Will generate an ASCII digit [`char`], see [`ascii_digit`](crate::ascii_digit)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_digit(&mut self) -> char
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_digit_from(self)
+	}
+
+	/// Will generate a [`String`] of `len` ASCII digits, see [`ascii_digit_string`](crate::ascii_digit_string)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn ascii_digit_string(&mut self, len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::ascii_digit_string_from(self, len)
+	}
+
+	/// Will generate an ASCII letter or digit [`char`], see [`alphanumeric`](crate::alphanumeric)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn alphanumeric(&mut self) -> char
+	where
+		Self: Sized,
+	{
+		crate::strings::alphanumeric_from(self)
+	}
+
+	/// Will generate a [`String`] of `len` ASCII letters and digits, see [`alphanumeric_string`](crate::alphanumeric_string)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn alphanumeric_string(&mut self, len: usize) -> String
+	where
+		Self: Sized,
+	{
+		crate::strings::alphanumeric_string_from(self, len)
+	}
+
+	/// Will generate a relative path of `depth` alphanumeric segments, see [`random_path`](crate::random_path)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_path(&mut self, depth: usize, segment_len: usize) -> std::path::PathBuf
+	where
+		Self: Sized,
+	{
+		crate::strings::random_path_from(self, depth, segment_len)
+	}
+
+	/// Will return `default` with probability `chance_none`, and a fresh `T` otherwise, see [`random_or`](crate::random_or)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_or<T: Random>(&mut self, chance_none: f64, default: T) -> T
+	where
+		Self: Sized,
+	{
+		assert!(
+			(0.0..=1.0).contains(&chance_none),
+			"probability must be in [0, 1]: {chance_none}"
+		);
+
+		if f64::random_from(self) < chance_none {
+			default
+		} else {
+			T::random_from(self)
+		}
+	}
+
+	/// Will run `f` with probability `p` and return its result, see [`with_probability`](crate::with_probability)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn with_probability<R, F: FnOnce() -> R>(&mut self, p: f64, f: F) -> Option<R>
+	where
+		Self: Sized,
+	{
+		assert!(
+			(0.0..=1.0).contains(&p),
+			"probability must be in [0, 1]: {p}"
+		);
+
+		(f64::random_from(self) < p).then(f)
+	}
+
+	/// Will draw a random `T` and clamp it into `[min, max]`, not uniform, see [`clamped`](crate::clamped)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn clamped<T: Random + Ord>(&mut self, min: T, max: T) -> T
+	where
+		Self: Sized,
+	{
+		T::random_from(self).clamp(min, max)
+	}
+
+	/// Will return a uniformly chosen value of `T`, see [`random_variant`](crate::random_variant)
+	#[inline]
+	fn random_variant<T: RandomVariant>(&mut self) -> T
+	where
+		Self: Sized,
+	{
+		assert!(T::COUNT > 0, "can't pick a variant of an empty type");
+
+		T::from_index(self.below(T::COUNT as u64) as usize)
+	}
+
+	/// Will generate a "full jitter" backoff delay, see [`full_jitter`](crate::full_jitter)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn full_jitter(&mut self, base: Duration, cap: Duration, attempt: u32) -> Duration
+	where
+		Self: Sized,
+	{
+		crate::full_jitter_from(self, base, cap, attempt)
+	}
+
+	/// Will generate a [`Duration`] of whole milliseconds, uniform in `[0, max_ms]`
+	#[inline]
+	fn duration_millis(&mut self, max_ms: u64) -> Duration {
+		Duration::from_millis(self.point_in_inclusive(0..=max_ms))
+	}
+
+	/// Will generate a [`Duration`] of whole seconds, uniform in `[0, max_secs]`
+	#[inline]
+	fn duration_secs(&mut self, max_secs: u64) -> Duration {
+		Duration::from_secs(self.point_in_inclusive(0..=max_secs))
+	}
+
+	/// Will generate `count` increasing timestamps after `start`, see [`monotonic_times`](crate::monotonic_times)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn monotonic_times(
+		&mut self,
+		start: SystemTime,
+		max_gap: Duration,
+		count: usize,
+	) -> Vec<SystemTime>
+	where
+		Self: Sized,
+	{
+		crate::monotonic_times_from(self, start, max_gap, count)
+	}
+
+	/// Will generate a uniform [`u64`] in `[min(a, b), max(a, b))`, or `a` if the two are equal
+	#[inline]
+	fn saturating_range(&mut self, a: u64, b: u64) -> u64 {
+		if a == b {
+			return a;
+		}
+
+		self.range(a.min(b), a.max(b))
+	}
+
+	/// Will generate a uniformly chosen multiple of `step` in `[low, high)`, see [`range_step`](crate::range_step)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn range_step(&mut self, low: u64, high: u64, step: u64) -> u64
+	where
+		Self: Sized,
+	{
+		crate::range_step_from(self, low, high, step)
+	}
+
+	/// Will fill `buf` with uniform values in `[low, high)`, see [`fill_range`](crate::fill_range)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn fill_range<T: RangeInt>(&mut self, buf: &mut [T], low: T, high: T)
+	where
+		Self: Sized,
+	{
+		crate::fill_range_from(self, buf, low, high)
+	}
+
+	/// Will generate a uniform [`u64`] in `range`
+	///
+	/// # Panics
+	/// If `range` is empty
+	#[inline]
+	fn point_in(&mut self, range: Range<u64>) -> u64 {
+		assert!(!range.is_empty(), "range is empty: {range:?}");
+
+		range.start + self.below(range.end - range.start)
+	}
+
+	/// Will generate a uniform [`u64`] in `range`, both ends included
+	///
+	/// # Panics
+	/// If `range` is empty
+	#[inline]
+	fn point_in_inclusive(&mut self, range: RangeInclusive<u64>) -> u64 {
+		assert!(!range.is_empty(), "range is empty: {range:?}");
+
+		let (start, end) = range.into_inner();
+
+		match (end - start).checked_add(1) {
+			Some(len) => start + self.below(len),
+			// The whole `u64` domain
+			None => self.next_u64(),
+		}
+	}
+
+	/// Will generate a uniform [`u64`] in `1..=n`
+	///
+	/// # Panics
+	/// If `n == 0`
+	#[inline]
+	fn nonzero_below(&mut self, n: u64) -> u64 {
+		assert!(n > 0, "can't draw from an empty range 1..=0");
+
+		1 + self.below(n)
+	}
+
+	/// Will generate a [`u64`] that is not in `blocked`, or [`None`] after `max_tries` draws hit it
+	#[inline]
+	fn u64_excluding(&mut self, blocked: &HashSet<u64>, max_tries: usize) -> Option<u64> {
+		(0..max_tries)
+			.map(|_| self.next_u64())
+			.find(|r| !blocked.contains(r))
+	}
+
+	/// Will generate a [`u64`] uniformly over the union of `intervals`, see [`range_multi`](crate::range_multi)
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn range_multi(&mut self, intervals: &[Range<u64>]) -> Option<u64>
+	where
+		Self: Sized,
+	{
+		crate::range_multi_from(self, intervals)
+	}
+
+	/// Will return `n` fair coin flips packed into the low bits of a [`u64`], see [`coin_flips`](crate::coin_flips)
+	#[inline]
+	fn coin_flips(&mut self, n: u32) -> u64 {
+		assert!(n <= 64, "can't pack {n} flips into a u64");
+
+		// High bits are taken, `n == 0` is a shift by 64
+		self.next_u64().checked_shr(64 - n).unwrap_or(0)
+	}
+
+	/// Will generate a uniform [`u64`] in `[0, 2^N)`, see [`bits`](crate::bits)
+	#[inline]
+	fn bits<const N: u32>(&mut self) -> u64
+	where
+		Self: Sized,
+	{
+		const { assert!(N <= 64, "can't fit more than 64 bits into a u64") };
+
+		// `N == 0` is a shift by 64
+		self.next_u64().checked_shr(64 - N).unwrap_or(0)
+	}
+
+	/// Will generate a [`u64`] with the bits of a fresh draw in reverse order, see [`u64_reversed`](crate::u64_reversed)
+	#[inline]
+	fn u64_reversed(&mut self) -> u64 {
+		self.next_u64().reverse_bits()
+	}
+
+	/// Will return the bytes of a fresh [`u64`] draw in little-endian order
+	#[inline]
+	fn next_u64_le(&mut self) -> [u8; 8] {
+		self.next_u64().to_le_bytes()
+	}
+
+	/// Will return the bytes of a fresh [`u64`] draw in big-endian (network) order
+	#[inline]
+	fn next_u64_be(&mut self) -> [u8; 8] {
+		self.next_u64().to_be_bytes()
+	}
+
+	/// Will generate a byte vector of a uniform length in `0..=max`, see [`random_bytes_up_to`](crate::random_bytes_up_to)
+	fn random_bytes_up_to(&mut self, max: usize) -> Vec<u8> {
+		let len = self.point_in_inclusive(0..=max as u64) as usize;
+
+		let mut buf = vec![0; len];
+		self.fill_bytes(&mut buf);
+
+		buf
+	}
+
+	/// Will generate a random byte array, filled 8 bytes per draw
+	#[inline]
+	fn random_byte_array<const N: usize>(&mut self) -> [u8; N]
+	where
+		Self: Sized,
+	{
+		let mut arr = [0; N];
+		self.fill_bytes(&mut arr);
+		arr
+	}
+
+	/// Will XOR random bytes into `buf` in place, see [`xor_bytes`](crate::xor_bytes)
+	#[inline]
+	fn xor_bytes(&mut self, buf: &mut [u8]) {
+		for_each_byte(self, buf, |dst, src| *dst ^= src);
+	}
+
+	/// Will generate a random IPv4 endpoint with a random port
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_socketaddr_v4(&mut self) -> SocketAddrV4
+	where
+		Self: Sized,
+	{
+		self.generate()
+	}
+
+	/// Will generate a random IPv6 endpoint with a random port
+	#[inline]
+	#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+	fn random_socketaddr_v6(&mut self) -> SocketAddrV6
+	where
+		Self: Sized,
+	{
+		self.generate()
+	}
+
+	/// Will generate a random `(r, g, b)` color, see [`color::rgb`](crate::color::rgb)
+	#[inline]
+	#[cfg(all(
+		feature = "color",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn rgb(&mut self) -> (u8, u8, u8)
+	where
+		Self: Sized,
+	{
+		crate::color::rgb_from(self)
+	}
+
+	/// Will generate a random `(r, g, b, a)` color, see [`color::rgba`](crate::color::rgba)
+	#[inline]
+	#[cfg(all(
+		feature = "color",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn rgba(&mut self) -> (u8, u8, u8, u8)
+	where
+		Self: Sized,
+	{
+		crate::color::rgba_from(self)
+	}
+
+	/// Will generate a random `(hue, saturation, lightness)` color, see [`color::hsl`](crate::color::hsl)
+	#[inline]
+	#[cfg(all(
+		feature = "color",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn hsl(&mut self) -> (f64, f64, f64)
+	where
+		Self: Sized,
+	{
+		crate::color::hsl_from(self)
+	}
+
+	/// Will generate a domain like `rand-xyz.com`, see [`fake::domain`](crate::fake::domain)
+	#[inline]
+	#[cfg(all(
+		feature = "fake",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn domain(&mut self) -> String
+	where
+		Self: Sized,
+	{
+		crate::fake::domain_from(self)
+	}
+
+	/// Will generate an email like `abc123@rand-xyz.com`, see [`fake::email`](crate::fake::email)
+	#[inline]
+	#[cfg(all(
+		feature = "fake",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn email(&mut self) -> String
+	where
+		Self: Sized,
+	{
+		crate::fake::email_from(self)
+	}
+
+	/// Will generate a random JSON tree nested at most `max_depth` levels deep, see
+	/// [`json::random_json_value`](crate::json::random_json_value)
+	#[inline]
+	#[cfg(all(
+		feature = "json",
+		any(target_os = "linux", target_os = "windows", target_os = "macos")
+	))]
+	fn random_json_value(&mut self, max_depth: usize) -> crate::json::JsonValue
+	where
+		Self: Sized,
+	{
+		crate::json::random_json_value_from(self, max_depth)
+	}
+}
+
+/// The process-wide generator behind the free functions, seeded at startup
+///
+/// # Example
+/// ```
+/// use hel_random::{Global, Source};
+///
+/// let r = Global.below(10);
+/// assert!(r < 10);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+pub struct Global;
+
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+impl Source for Global {
+	#[inline(always)]
+	fn next_u64(&mut self) -> u64 {
+		crate::u64()
+	}
+}
+
+/// A source replaying a recorded byte buffer instead of running a generator
+///
+/// Every draw takes the next 8 bytes in little-endian order, mirroring [`Source::fill_bytes`],
+/// so bytes captured with it replay the same values. A short final chunk is zero padded.
+///
+/// Once the buffer runs out every draw returns [`u64::MAX`], check [`ByteReader::is_exhausted`] to detect it.
+/// Bounded draws never reject that value, for 64 and 128 bit bounds alike, so they can't spin forever.
+///
+/// # Example
+/// ```
+/// use hel_random::{ByteReader, Source};
+///
+/// let recorded = [1, 0, 0, 0, 0, 0, 0, 0, 7];
+/// let mut source = ByteReader::new(&recorded);
+///
+/// assert_eq!(source.next_u64(), 1);
+/// assert_eq!(source.next_u64(), 7);
+/// assert!(source.is_exhausted());
+/// assert_eq!(source.below(10), 9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+	bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+	/// Will create a source reading from the start of `bytes`
+	pub fn new(bytes: &'a [u8]) -> Self {
+		Self { bytes }
+	}
+
+	/// Will return the number of bytes not consumed yet
+	pub fn remaining(&self) -> usize {
+		self.bytes.len()
+	}
+
+	/// Will return `true` once every byte has been consumed
+	pub fn is_exhausted(&self) -> bool {
+		self.bytes.is_empty()
+	}
+}
+
+impl Source for ByteReader<'_> {
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		if self.bytes.is_empty() {
+			return u64::MAX;
+		}
+
+		let (chunk, rest) = self.bytes.split_at(self.bytes.len().min(8));
+		self.bytes = rest;
+
+		let mut word = [0; 8];
+		word[..chunk.len()].copy_from_slice(chunk);
+
+		u64::from_le_bytes(word)
+	}
+}
+
+#[inline]
+pub(crate) fn for_each_byte<S: Source + ?Sized>(
+	source: &mut S,
+	buf: &mut [u8],
+	op: impl Fn(&mut u8, u8),
+) {
+	// One `u64` per 8 bytes, little endian, the tail takes the low bytes of the last draw
+	for chunk in buf.chunks_mut(8) {
+		for (dst, src) in chunk.iter_mut().zip(source.next_u64().to_le_bytes()) {
+			op(dst, src);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Replays a fixed sequence
+	struct Replay(Vec<u64>);
+
+	impl Source for Replay {
+		fn next_u64(&mut self) -> u64 {
+			self.0.remove(0)
+		}
+	}
+
+	#[test]
+	fn below_rejects_biased_draws() {
+		// For n = 3 the threshold is 1, so a low product half of 0 is rejected
+		let mut source = Replay(vec![0, u64::MAX]);
+		assert_eq!(source.below(3), 2);
+
+		let mut source = Replay(vec![u64::MAX / 2]);
+		assert_eq!(source.below(2), 0);
+	}
+
+	#[test]
+	fn fill_bytes_little_endian() {
+		let mut source = Replay(vec![0x0807060504030201, 0x0b0a09]);
+		let mut buf = [0; 11];
+		source.fill_bytes(&mut buf);
+
+		assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+	}
+
+	#[test]
+	fn custom_source_surface() {
+		let mut source = Replay(vec![u64::MAX; 16]);
+
+		assert_eq!(source.choose(&[1, 2, 3]), Some(&3));
+		assert_eq!(source.generate::<u8>(), u8::MAX);
+		assert!(source.generate::<bool>());
+
+		// Always drawing the top index swaps every element with itself
+		let mut v = [1, 2, 3, 4];
+		source.shuffle(&mut v);
+		assert_eq!(v, [1, 2, 3, 4]);
+	}
+
+	#[test]
+	fn byte_reader_replays_fill_bytes() {
+		let mut recorded = [0; 20];
+		Replay(vec![u64::MAX / 3, 42, 0xabcdef]).fill_bytes(&mut recorded);
+
+		let mut reader = ByteReader::new(&recorded);
+		assert_eq!(reader.remaining(), 20);
+		assert_eq!(reader.next_u64(), u64::MAX / 3);
+		assert_eq!(reader.next_u64(), 42);
+		assert_eq!(reader.next_u64(), 0xabcdef);
+		assert!(reader.is_exhausted());
+
+		// Exhausted draws are never rejected
+		assert_eq!(reader.next_u64(), u64::MAX);
+		assert_eq!(reader.below(3), 2);
+		assert!(ByteReader::new(&[]).is_exhausted());
+	}
+}
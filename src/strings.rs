@@ -0,0 +1,619 @@
+use std::path::PathBuf;
+
+use crate::{weighted::sample_cdf_from, Global, Random, Source};
+
+/// Will randomly upper or lowercase every ASCII letter of `s` with a fair coin
+///
+/// Everything else, including non-ASCII characters, is left untouched
+///
+/// # Example
+/// ```
+/// use hel_random::random_case;
+///
+/// let s = random_case("hello, wörld");
+///
+/// assert!(s.eq_ignore_ascii_case("hello, wörld"));
+/// println!("s = {s}");
+/// ```
+pub fn random_case(s: &str) -> String {
+	random_case_from(&mut Global, s)
+}
+
+pub(crate) fn random_case_from<S: Source>(source: &mut S, s: &str) -> String {
+	s.chars()
+		.map(|c| match c.is_ascii_alphabetic() {
+			true if bool::random_from(source) => c.to_ascii_uppercase(),
+			true => c.to_ascii_lowercase(),
+			false => c,
+		})
+		.collect()
+}
+
+/// Will generate a valid UTF-8 byte sequence of at most `max_len` bytes
+///
+/// Random scalar values are encoded one after another, stopping at the first one that would
+/// exceed the byte budget. Most scalar values take 4 bytes, so short budgets are often not filled.
+///
+/// # Example
+/// ```
+/// use hel_random::random_utf8_bytes;
+///
+/// let bytes = random_utf8_bytes(64);
+///
+/// assert!(bytes.len() <= 64);
+/// assert!(std::str::from_utf8(&bytes).is_ok());
+/// ```
+pub fn random_utf8_bytes(max_len: usize) -> Vec<u8> {
+	random_utf8_bytes_from(&mut Global, max_len)
+}
+
+pub(crate) fn random_utf8_bytes_from<S: Source>(source: &mut S, max_len: usize) -> Vec<u8> {
+	let mut res = Vec::with_capacity(max_len);
+	let mut buf = [0; 4];
+
+	loop {
+		let c = char::random_from(source);
+
+		if res.len() + c.len_utf8() > max_len {
+			return res;
+		}
+
+		res.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+	}
+}
+
+/// Will generate a valid UTF-16 sequence of at most `max_units` code units
+///
+/// Handy for fuzzing Windows paths. Random scalar values are encoded one after another, so astral
+/// characters always come as a full surrogate pair and a lone surrogate is never produced.
+/// Encoding stops at the first character that would exceed the budget, most scalar values take 2 units.
+///
+/// # Example
+/// ```
+/// use hel_random::random_utf16;
+///
+/// let units = random_utf16(64);
+///
+/// assert!(units.len() <= 64);
+/// assert!(String::from_utf16(&units).is_ok());
+/// ```
+pub fn random_utf16(max_units: usize) -> Vec<u16> {
+	random_utf16_from(&mut Global, max_units)
+}
+
+pub(crate) fn random_utf16_from<S: Source>(source: &mut S, max_units: usize) -> Vec<u16> {
+	let mut res = Vec::with_capacity(max_units);
+	let mut buf = [0; 2];
+
+	loop {
+		let c = char::random_from(source);
+
+		if res.len() + c.len_utf16() > max_units {
+			return res;
+		}
+
+		res.extend_from_slice(c.encode_utf16(&mut buf));
+	}
+}
+
+/// Will generate a printable ASCII [`char`], uniform over the 95 code points from `' '` to `'~'`
+///
+/// Never produces a control character
+///
+/// # Example
+/// ```
+/// use hel_random::ascii_printable;
+///
+/// let c = ascii_printable();
+///
+/// assert!(c.is_ascii() && !c.is_ascii_control());
+/// ```
+#[inline]
+pub fn ascii_printable() -> char {
+	ascii_printable_from(&mut Global)
+}
+
+#[inline]
+pub(crate) fn ascii_printable_from<S: Source>(source: &mut S) -> char {
+	(b' ' + source.below(95) as u8) as char
+}
+
+/// Will generate a [`String`] of `len` printable ASCII characters, see [`ascii_printable`]
+///
+/// # Example
+/// ```
+/// use hel_random::ascii_printable_string;
+///
+/// let s = ascii_printable_string(16);
+///
+/// assert_eq!(s.len(), 16);
+/// assert!(s.chars().all(|c| (' '..='~').contains(&c)));
+/// ```
+pub fn ascii_printable_string(len: usize) -> String {
+	ascii_printable_string_from(&mut Global, len)
+}
+
+pub(crate) fn ascii_printable_string_from<S: Source>(source: &mut S, len: usize) -> String {
+	(0..len).map(|_| ascii_printable_from(source)).collect()
+}
+
+/// Will generate a uniform [`char`] with a scalar value in `lo..=hi`, skipping the surrogate gap
+///
+/// Bounds are raw code points, handy when walking Unicode blocks by number. `hi` is clamped to
+/// [`char::MAX`]. Returns [`None`] if no scalar value is left in the range, e.g. when it only covers surrogates.
+///
+/// # Example
+/// ```
+/// use hel_random::char_from_u32_range;
+///
+/// // Cyrillic
+/// let c = char_from_u32_range(0x0400, 0x04FF).unwrap();
+/// assert!(('\u{0400}'..='\u{04FF}').contains(&c));
+///
+/// // Straddles the surrogates, only the two ends are left
+/// let edge = char_from_u32_range(0xD7FF, 0xE000).unwrap();
+/// assert!(edge == '\u{D7FF}' || edge == '\u{E000}');
+///
+/// assert_eq!(char_from_u32_range(0xD800, 0xDFFF), None);
+/// ```
+pub fn char_from_u32_range(lo: u32, hi: u32) -> Option<char> {
+	char_from_u32_range_from(&mut Global, lo, hi)
+}
+
+pub(crate) fn char_from_u32_range_from<S: Source>(
+	source: &mut S,
+	lo: u32,
+	hi: u32,
+) -> Option<char> {
+	const SURROGATES_START: u32 = 0xD800;
+	const SURROGATES_END: u32 = 0xDFFF;
+
+	let hi = hi.min(char::MAX as u32);
+
+	// Scalar values on either side of the gap
+	let before_end = hi.min(SURROGATES_START - 1);
+	let before = if lo <= before_end {
+		before_end - lo + 1
+	} else {
+		0
+	};
+
+	let after_start = lo.max(SURROGATES_END + 1);
+	let after = if after_start <= hi {
+		hi - after_start + 1
+	} else {
+		0
+	};
+
+	let count = before as u64 + after as u64;
+
+	if count == 0 {
+		return None;
+	}
+
+	let n = source.below(count) as u32;
+	let scalar = if n < before {
+		lo + n
+	} else {
+		after_start + (n - before)
+	};
+
+	char::from_u32(scalar)
+}
+
+/// Will generate a [`String`] of `len` characters drawn from a character frequency table
+///
+/// Weights don't have to add up to anything, they are normalized, e.g. English letter frequencies
+/// in percent give text that looks a lot more natural than uniform characters.
+/// The table is turned into a CDF once and every character is a binary search, see [`sample_cdf`].
+///
+/// # Panics
+/// If `freqs` is empty or a weight isn't positive and finite
+///
+/// # Example
+/// ```
+/// use hel_random::text_from_frequencies;
+///
+/// let freqs = [('e', 12.7), ('t', 9.1), ('a', 8.2), (' ', 18.0)];
+/// let text = text_from_frequencies(&freqs, 40);
+///
+/// assert_eq!(text.chars().count(), 40);
+/// assert!(text.chars().all(|c| "eta ".contains(c)));
+/// ```
+pub fn text_from_frequencies(freqs: &[(char, f64)], len: usize) -> String {
+	text_from_frequencies_from(&mut Global, freqs, len)
+}
+
+pub(crate) fn text_from_frequencies_from<S: Source>(
+	source: &mut S,
+	freqs: &[(char, f64)],
+	len: usize,
+) -> String {
+	assert!(!freqs.is_empty(), "frequency table is empty");
+	assert!(
+		freqs.iter().all(|(_, w)| w.is_finite() && *w > 0.0),
+		"frequencies must be positive and finite"
+	);
+
+	let total: f64 = freqs.iter().map(|(_, w)| w).sum();
+	let cdf: Vec<f64> = freqs
+		.iter()
+		.scan(0.0, |acc, (_, w)| {
+			*acc += w;
+			Some(*acc / total)
+		})
+		.collect();
+
+	(0..len)
+		.map(|_| freqs[sample_cdf_from(source, &cdf)].0)
+		.collect()
+}
+
+macro_rules! ascii_class {
+	(
+		$fn_name: ident,
+		$from_name: ident,
+		$string_name: ident,
+		$string_from_name: ident,
+		$first: literal,
+		$count: literal,
+		$what: literal,
+		$check: ident
+	) => {
+		#[doc = concat!("Will generate an ASCII ", $what, " [`char`], uniform over the ", $count, " of them")]
+		///
+		/// # Example
+		/// ```
+		#[doc = concat!("let c = hel_random::", stringify!($fn_name), "();")]
+		///
+		#[doc = concat!("assert!(c.", stringify!($check), "());")]
+		/// ```
+		#[inline]
+		pub fn $fn_name() -> char {
+			$from_name(&mut Global)
+		}
+
+		#[inline]
+		pub(crate) fn $from_name<S: Source>(source: &mut S) -> char {
+			($first + source.below($count) as u8) as char
+		}
+
+		#[doc = concat!("Will generate a [`String`] of `len` ASCII ", $what, "s, see [`", stringify!($fn_name), "`]")]
+		///
+		/// # Example
+		/// ```
+		#[doc = concat!("let s = hel_random::", stringify!($string_name), "(8);")]
+		///
+		/// assert_eq!(s.len(), 8);
+		#[doc = concat!("assert!(s.chars().all(|c| c.", stringify!($check), "()));")]
+		/// ```
+		pub fn $string_name(len: usize) -> String {
+			$string_from_name(&mut Global, len)
+		}
+
+		pub(crate) fn $string_from_name<S: Source>(source: &mut S, len: usize) -> String {
+			(0..len).map(|_| $from_name(source)).collect()
+		}
+	};
+}
+
+ascii_class!(
+	ascii_lowercase,
+	ascii_lowercase_from,
+	ascii_lowercase_string,
+	ascii_lowercase_string_from,
+	b'a',
+	26,
+	"lowercase letter",
+	is_ascii_lowercase
+);
+ascii_class!(
+	ascii_uppercase,
+	ascii_uppercase_from,
+	ascii_uppercase_string,
+	ascii_uppercase_string_from,
+	b'A',
+	26,
+	"uppercase letter",
+	is_ascii_uppercase
+);
+ascii_class!(
+	ascii_digit,
+	ascii_digit_from,
+	ascii_digit_string,
+	ascii_digit_string_from,
+	b'0',
+	10,
+	"digit",
+	is_ascii_digit
+);
+
+/// Will generate an ASCII letter or digit [`char`], uniform over the 62 of them
+///
+/// # Example
+/// ```
+/// let c = hel_random::alphanumeric();
+///
+/// assert!(c.is_ascii_alphanumeric());
+/// ```
+#[inline]
+pub fn alphanumeric() -> char {
+	alphanumeric_from(&mut Global)
+}
+
+#[inline]
+pub(crate) fn alphanumeric_from<S: Source>(source: &mut S) -> char {
+	const ALPHABET: &[u8; 62] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+	ALPHABET[source.below(62) as usize] as char
+}
+
+/// Will generate a [`String`] of `len` ASCII letters and digits, see [`alphanumeric`]
+///
+/// # Example
+/// ```
+/// let token = hel_random::alphanumeric_string(12);
+///
+/// assert_eq!(token.len(), 12);
+/// assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+/// ```
+pub fn alphanumeric_string(len: usize) -> String {
+	alphanumeric_string_from(&mut Global, len)
+}
+
+pub(crate) fn alphanumeric_string_from<S: Source>(source: &mut S, len: usize) -> String {
+	(0..len).map(|_| alphanumeric_from(source)).collect()
+}
+
+/// Will generate a relative path of `depth` segments, each an [`alphanumeric_string`] of `segment_len`
+///
+/// Segments never contain separators or characters reserved on any platform, and are never a Windows
+/// device name such as `CON` or `lpt1`
+///
+/// # Panics
+/// If `depth > 0` and `segment_len == 0`
+///
+/// # Example
+/// ```
+/// use hel_random::random_path;
+///
+/// let path = random_path(3, 8);
+///
+/// assert!(path.is_relative());
+/// assert_eq!(path.components().count(), 3);
+/// ```
+pub fn random_path(depth: usize, segment_len: usize) -> PathBuf {
+	random_path_from(&mut Global, depth, segment_len)
+}
+
+pub(crate) fn random_path_from<S: Source>(
+	source: &mut S,
+	depth: usize,
+	segment_len: usize,
+) -> PathBuf {
+	assert!(
+		depth == 0 || segment_len > 0,
+		"path segments can't be empty"
+	);
+
+	(0..depth)
+		.map(|_| loop {
+			let segment = alphanumeric_string_from(source, segment_len);
+
+			if !is_windows_device_name(&segment) {
+				break segment;
+			}
+		})
+		.collect()
+}
+
+// Windows won't create files named after a device, whatever the case or extension
+fn is_windows_device_name(segment: &str) -> bool {
+	let stem = segment.split('.').next().unwrap_or_default();
+
+	match stem.to_ascii_uppercase().as_bytes() {
+		b"CON" | b"PRN" | b"AUX" | b"NUL" => true,
+		[b'C', b'O', b'M', digit] | [b'L', b'P', b'T', digit] => (b'1'..=b'9').contains(digit),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn utf8_bytes_are_valid() {
+		for max_len in [0, 1, 3, 4, 5, 100, 1000] {
+			for _ in 0..100 {
+				let bytes = random_utf8_bytes(max_len);
+
+				assert!(bytes.len() <= max_len);
+				assert!(std::str::from_utf8(&bytes).is_ok());
+			}
+		}
+
+		assert!(random_utf8_bytes(1000).len() > 900);
+	}
+
+	#[test]
+	fn utf16_is_valid() {
+		for max_units in [0, 1, 2, 3, 100, 1000] {
+			for _ in 0..100 {
+				let units = random_utf16(max_units);
+
+				assert!(units.len() <= max_units);
+				assert!(String::from_utf16(&units).is_ok());
+			}
+		}
+
+		assert!(random_utf16(1000).len() > 990);
+	}
+
+	#[test]
+	fn printable_covers_all() {
+		let mut seen = [false; 95];
+
+		for c in ascii_printable_string(10_000).chars() {
+			assert!((' '..='~').contains(&c));
+			seen[(c as u8 - b' ') as usize] = true;
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert!(ascii_printable_string(0).is_empty());
+	}
+
+	#[test]
+	fn ascii_classes_cover_all() {
+		let lower: std::collections::HashSet<char> =
+			ascii_lowercase_string(5_000).chars().collect();
+		let upper: std::collections::HashSet<char> =
+			ascii_uppercase_string(5_000).chars().collect();
+		let digits: std::collections::HashSet<char> = ascii_digit_string(5_000).chars().collect();
+
+		assert_eq!(lower, ('a'..='z').collect());
+		assert_eq!(upper, ('A'..='Z').collect());
+		assert_eq!(digits, ('0'..='9').collect());
+		assert!(ascii_digit_string(0).is_empty());
+	}
+
+	#[test]
+	fn text_follows_frequencies() {
+		const LEN: usize = 100_000;
+
+		let text = text_from_frequencies(&[('x', 1.0), ('y', 3.0), ('ü', 0.001)], LEN);
+		let ys = text.chars().filter(|&c| c == 'y').count();
+
+		assert_eq!(text.chars().count(), LEN);
+		assert!((ys as f64 / LEN as f64 - 0.75).abs() < 0.01);
+		assert!(text_from_frequencies(&[('a', 1.0)], 5) == "aaaaa");
+		assert!(text_from_frequencies(&[('a', 1.0)], 0).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn text_zero_frequency() {
+		text_from_frequencies(&[('a', 1.0), ('b', 0.0)], 5);
+	}
+
+	#[test]
+	fn chars_from_u32_ranges() {
+		let mut seen = [false; 4];
+
+		for _ in 0..1_000 {
+			let c = char_from_u32_range(0xD7FE, 0xE001).unwrap() as u32;
+
+			match c {
+				0xD7FE | 0xD7FF => seen[(c - 0xD7FE) as usize] = true,
+				0xE000 | 0xE001 => seen[(c - 0xE000 + 2) as usize] = true,
+				_ => panic!("out of range: {c:#x}"),
+			}
+
+			assert!(char_from_u32_range(0, u32::MAX).is_some());
+		}
+
+		assert!(seen.iter().all(|&s| s));
+		assert_eq!(char_from_u32_range(0x41, 0x41), Some('A'));
+		assert_eq!(char_from_u32_range(0xDC00, 0xDC10), None);
+		assert_eq!(char_from_u32_range(0x110000, u32::MAX), None);
+		assert_eq!(char_from_u32_range(5, 4), None);
+		assert_eq!(char_from_u32_range(0xDFFF, 0xE000), Some('\u{E000}'));
+	}
+
+	#[test]
+	fn alphanumeric_covers_all() {
+		let seen: std::collections::HashSet<char> = alphanumeric_string(10_000).chars().collect();
+
+		assert_eq!(seen.len(), 62);
+		assert!(seen.iter().all(|c| c.is_ascii_alphanumeric()));
+	}
+
+	#[test]
+	fn paths() {
+		let path = random_path(4, 6);
+		let segments: Vec<_> = path.iter().map(|s| s.to_str().unwrap()).collect();
+
+		assert_eq!(segments.len(), 4);
+		assert!(segments
+			.iter()
+			.all(|s| s.len() == 6 && s.chars().all(|c| c.is_ascii_alphanumeric())));
+		assert_eq!(random_path(0, 0), PathBuf::new());
+	}
+
+	#[test]
+	fn device_names_are_filtered() {
+		for name in [
+			"CON",
+			"con",
+			"Nul",
+			"aux.txt",
+			"PRN.tar.gz",
+			"COM1",
+			"lpt9",
+			"Com5.log",
+		] {
+			assert!(is_windows_device_name(name), "{name}");
+		}
+
+		for name in [
+			"CONS", "XCON", "COM0", "LPT", "COM10", "COMA", "nul_", "file.CON", "",
+		] {
+			assert!(!is_windows_device_name(name), "{name}");
+		}
+
+		// Three letter segments hit `con` and friends, in any case, about once in 7k draws
+		for _ in 0..1_000 {
+			let path = random_path(100, 3);
+			assert!(path
+				.iter()
+				.all(|s| !is_windows_device_name(s.to_str().unwrap())));
+		}
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = crate::Rng::from_seed(144);
+
+			(
+				rng.random_case("hello, world"),
+				rng.random_utf8_bytes(32),
+				rng.random_utf16(32),
+				rng.ascii_printable(),
+				rng.ascii_printable_string(8),
+				rng.char_from_u32_range(0x0400, 0x04FF),
+				rng.text_from_frequencies(&[('e', 12.7), ('t', 9.1), (' ', 18.0)], 16),
+				(rng.ascii_lowercase(), rng.ascii_lowercase_string(8)),
+				(rng.ascii_uppercase(), rng.ascii_uppercase_string(8)),
+				(rng.ascii_digit(), rng.ascii_digit_string(8)),
+				(rng.alphanumeric(), rng.alphanumeric_string(8)),
+				rng.random_path(3, 4),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	#[should_panic]
+	fn path_empty_segments() {
+		random_path(2, 0);
+	}
+
+	#[test]
+	fn case_only_changes_letters() {
+		let input = "The Quick Brown Fox! 123 ß ÄÖÜ";
+
+		for _ in 0..100 {
+			let output = random_case(input);
+
+			assert_eq!(output.len(), input.len());
+			assert!(output.eq_ignore_ascii_case(input));
+			assert!(output.ends_with("! 123 ß ÄÖÜ"));
+		}
+
+		let long = "a".repeat(100);
+		let output = random_case(&long);
+		assert!(output.contains('a') && output.contains('A'));
+	}
+}
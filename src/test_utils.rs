@@ -0,0 +1,332 @@
+//! Statistical self-tests to sanity check the generator on your platform
+
+#[cfg(feature = "test-hooks")]
+use std::{cell::RefCell, collections::VecDeque};
+
+use std::time::{Duration, Instant};
+
+use crate::{shuffle, u64, with_state, xoshiro256pp};
+
+#[cfg(feature = "test-hooks")]
+thread_local! {
+	// Values `u64()` hands out before the generator, see `set_next_values`
+	static NEXT_VALUES: RefCell<VecDeque<u64>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Will shuffle `[0..n]` `trials` times and return the chi-square statistic of how often every
+/// element lands in every position, against the uniform expectation
+///
+/// Every element contributes `n - 1` degrees of freedom, so for a fair shuffle the statistic is
+/// expected to be around `n * (n - 1)`
+///
+/// # Panics
+/// If `n` or `trials` is `0`
+///
+/// # Example
+/// ```
+/// use hel_random::test_utils::shuffle_uniformity;
+///
+/// let n = 8;
+/// let chi2 = shuffle_uniformity(n, 10_000);
+/// let expected = (n * (n - 1)) as f64;
+///
+/// // Way more than enough slack for a fair shuffle
+/// assert!(chi2 < expected * 2.0);
+/// ```
+pub fn shuffle_uniformity(n: usize, trials: usize) -> f64 {
+	assert!(
+		n > 0 && trials > 0,
+		"need at least one element and one trial"
+	);
+
+	let mut counts = vec![0u64; n * n];
+	let mut perm: Vec<usize> = (0..n).collect();
+
+	for _ in 0..trials {
+		perm.iter_mut().enumerate().for_each(|(i, p)| *p = i);
+		shuffle(&mut perm);
+
+		for (pos, &elem) in perm.iter().enumerate() {
+			counts[elem * n + pos] += 1;
+		}
+	}
+
+	let expected = trials as f64 / n as f64;
+
+	counts
+		.iter()
+		.map(|&c| (c as f64 - expected).powi(2) / expected)
+		.sum()
+}
+
+/// Will draw `samples` [`u64`]s and return the fraction of draws with each bit set, bit `0` first
+///
+/// Every entry should be close to `0.5`, within a few `0.5 / sqrt(samples)`
+///
+/// # Panics
+/// If `samples` is `0`
+///
+/// # Example
+/// ```
+/// use hel_random::test_utils::bit_frequency;
+///
+/// let freq = bit_frequency(100_000);
+///
+/// assert!(freq.iter().all(|f| (f - 0.5).abs() < 0.01));
+/// ```
+pub fn bit_frequency(samples: usize) -> [f64; 64] {
+	assert!(samples > 0, "need at least one sample");
+
+	let mut counts = [0u64; 64];
+
+	for _ in 0..samples {
+		let x = u64();
+
+		for (bit, count) in counts.iter_mut().enumerate() {
+			*count += (x >> bit) & 1;
+		}
+	}
+
+	counts.map(|c| c as f64 / samples as f64)
+}
+
+/// Will draw `samples` values of [`u64()`] scaled to `[0, 1)` and return their
+/// lag-`lag` autocorrelation coefficient
+///
+/// For uncorrelated outputs it is close to `0`, within a few multiples of `1 / sqrt(samples)`.
+/// Lag `0` is always `1`.
+///
+/// # Panics
+/// If `samples` isn't greater than `lag`
+///
+/// # Example
+/// ```
+/// use hel_random::test_utils::autocorrelation;
+///
+/// let r = autocorrelation(1, 100_000);
+///
+/// assert!(r.abs() < 0.02);
+/// ```
+pub fn autocorrelation(lag: usize, samples: usize) -> f64 {
+	assert!(
+		samples > lag,
+		"need more than {lag} samples for a lag of {lag}"
+	);
+
+	let xs: Vec<f64> = (0..samples).map(|_| u64() as f64 / 2f64.powi(64)).collect();
+	let mean = xs.iter().sum::<f64>() / samples as f64;
+
+	let variance: f64 = xs.iter().map(|x| (x - mean) * (x - mean)).sum();
+	let covariance: f64 = xs
+		.iter()
+		.zip(&xs[lag..])
+		.map(|(a, b)| (a - mean) * (b - mean))
+		.sum();
+
+	covariance / variance
+}
+
+/// Will draw [`u64()`] values for about `duration` of wall-clock time and return how many it drew
+///
+/// An in-process counterpart to the benches, e.g. to log the generator speed at startup.
+/// The clock is checked every 1024 draws, so the result is a multiple of that and can overshoot
+/// `duration` by one batch.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use hel_random::test_utils::throughput;
+///
+/// let draws = throughput(Duration::from_millis(10));
+/// println!("{} million draws per second", draws / 10_000);
+/// ```
+pub fn throughput(duration: Duration) -> u64 {
+	const BATCH: u64 = 1024;
+
+	let start = Instant::now();
+	let mut count = 0;
+
+	while start.elapsed() < duration {
+		for _ in 0..BATCH {
+			std::hint::black_box(u64());
+		}
+
+		count += BATCH;
+	}
+
+	count
+}
+
+/// Will walk a copy of the global state up to `max_steps` steps ahead and return the cycle length
+/// if the state comes back to where it started, [`None`] otherwise
+///
+/// The global generator isn't advanced. The xoshiro256++ transition is a bijection with a single
+/// cycle of length `2^256 - 1` over all nonzero states, so for any feasible `max_steps` this
+/// is expected to be [`None`]. A [`Some`] means the state is broken.
+///
+/// # Example
+/// ```
+/// use hel_random::test_utils::detect_short_cycle;
+///
+/// assert_eq!(detect_short_cycle(100_000), None);
+/// ```
+pub fn detect_short_cycle(max_steps: u64) -> Option<u64> {
+	let start = with_state(|state| *state);
+	let mut state = start;
+
+	// A bijection can only return to a state through the starting one
+	for step in 1..=max_steps {
+		xoshiro256pp(&mut state);
+
+		if state == start {
+			return Some(step);
+		}
+	}
+
+	None
+}
+
+/// Will make the next `values.len()` global draws on this thread return `values` in order,
+/// after that the real generator takes over again
+///
+/// Everything built on [`u64()`] consumes the queue, [`f64()`](crate::f64()), [`range`](crate::range), [`shuffle`]
+/// and so on, so code paths like "when the generator returns 0" can be tested deterministically.
+/// Queued values don't advance the generator. Calling it again replaces whatever is still queued.
+///
+/// Only available with the `test-hooks` feature, the queue is thread-local.
+///
+/// # Example
+/// ```
+/// use hel_random::test_utils::set_next_values;
+///
+/// set_next_values(vec![7, 0]);
+///
+/// assert_eq!(hel_random::u64(), 7);
+/// assert_eq!(hel_random::f64(), 0.0);
+/// ```
+#[cfg(feature = "test-hooks")]
+pub fn set_next_values(values: Vec<u64>) {
+	NEXT_VALUES.with_borrow_mut(|queue| *queue = values.into());
+}
+
+/// Will pop the next value queued with [`set_next_values`]
+#[cfg(feature = "test-hooks")]
+#[inline]
+pub(crate) fn take_next_value() -> Option<u64> {
+	NEXT_VALUES.with_borrow_mut(VecDeque::pop_front)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Global, Source};
+
+	#[test]
+	fn shuffle_is_uniform() {
+		let n = 10;
+		let chi2 = shuffle_uniformity(n, 20_000);
+		let expected = (n * (n - 1)) as f64;
+
+		println!("Chi-square: {chi2}, expected: {expected}");
+		// The mean is 90 with a standard deviation of about 14, this is over 6 of them
+		assert!(chi2 < expected * 2.0);
+
+		assert_eq!(shuffle_uniformity(1, 10), 0.0);
+	}
+
+	#[test]
+	fn bits_are_balanced() {
+		let freq = bit_frequency(200_000);
+
+		println!("Bit frequencies: {freq:?}");
+		// About 9 standard deviations
+		assert!(freq.iter().all(|f| (f - 0.5).abs() < 0.01));
+	}
+
+	#[test]
+	fn uncorrelated_lags() {
+		for lag in [1, 2, 7, 64] {
+			let r = autocorrelation(lag, 200_000);
+
+			println!("Lag {lag}: {r}");
+			// About 5 standard deviations
+			assert!(r.abs() < 0.012);
+		}
+
+		assert!((autocorrelation(0, 100) - 1.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn throughput_counts() {
+		let draws = throughput(Duration::from_millis(20));
+
+		println!("Draws in 20ms: {draws}");
+		assert!(draws > 0);
+		assert_eq!(draws % 1024, 0);
+
+		assert_eq!(throughput(Duration::ZERO), 0);
+	}
+
+	#[test]
+	fn no_short_cycle() {
+		assert_eq!(detect_short_cycle(1_000_000), None);
+		assert_eq!(detect_short_cycle(0), None);
+	}
+
+	#[test]
+	fn biased_shuffle_is_detected() {
+		// Same statistic over a naive "swap with any index" shuffle, which is known to be biased
+		let n = 5;
+		let trials = 100_000;
+
+		let mut counts = vec![0u64; n * n];
+
+		for _ in 0..trials {
+			let mut perm: Vec<usize> = (0..n).collect();
+
+			for i in 0..n {
+				perm.swap(i, Global.below(n as u64) as usize);
+			}
+
+			for (pos, &elem) in perm.iter().enumerate() {
+				counts[elem * n + pos] += 1;
+			}
+		}
+
+		let expected = trials as f64 / n as f64;
+		let chi2: f64 = counts
+			.iter()
+			.map(|&c| (c as f64 - expected).powi(2) / expected)
+			.sum();
+
+		let fair = shuffle_uniformity(n, trials);
+
+		println!("Biased: {chi2}, fair: {fair}");
+		assert!(chi2 > fair * 10.0);
+	}
+
+	#[test]
+	#[cfg(feature = "test-hooks")]
+	fn queued_values_come_first() {
+		set_next_values(vec![1, 2, u64::MAX]);
+
+		assert_eq!(u64(), 1);
+		assert_eq!(u64(), 2);
+		assert_eq!(crate::range(10, 20), 19);
+
+		// Then the generator is back
+		let real: Vec<u64> = (0..4).map(|_| u64()).collect();
+		assert!(real.windows(2).all(|w| w[0] != w[1]));
+
+		// Other threads don't see the queue
+		set_next_values(vec![0; 8]);
+		std::thread::spawn(|| assert_ne!(u64() | u64(), 0))
+			.join()
+			.unwrap();
+		assert_eq!(u64(), 0);
+
+		set_next_values(Vec::new());
+		assert_ne!(u64() | u64(), 0);
+	}
+}
@@ -0,0 +1,493 @@
+use crate::{below_u128_from, Global, Random, Source};
+
+/// Will return a bucket index drawn from a cumulative distribution function
+///
+/// `cdf` must be non-decreasing and end at `1.0`, bucket `i` is picked with probability
+/// `cdf[i] - cdf[i - 1]`. Each draw is a binary search, so the same `cdf` can be reused cheaply.
+/// Monotonicity is only validated in debug builds.
+///
+/// # Panics
+/// If `cdf` is empty
+///
+/// # Example
+/// ```
+/// use hel_random::sample_cdf;
+///
+/// // 10%, 60%, 30%
+/// let cdf = [0.1, 0.7, 1.0];
+/// let bucket = sample_cdf(&cdf);
+///
+/// assert!(bucket < 3);
+/// ```
+pub fn sample_cdf(cdf: &[f64]) -> usize {
+	sample_cdf_from(&mut Global, cdf)
+}
+
+pub(crate) fn sample_cdf_from<S: Source>(source: &mut S, cdf: &[f64]) -> usize {
+	assert!(!cdf.is_empty(), "cdf is empty");
+	debug_assert!(
+		cdf.windows(2).all(|w| w[0] <= w[1]),
+		"cdf is not non-decreasing"
+	);
+
+	let u = f64::random_from(source);
+
+	// Clamped in case rounding left the last entry slightly below 1
+	cdf.partition_point(|&c| c <= u).min(cdf.len() - 1)
+}
+
+/// Will draw `k` distinct indices, each pick proportional to its weight among the ones not picked yet
+///
+/// This is the classic weighted lottery: after every pick the winner's weight is removed from the total.
+/// Indices are returned in the order they were drawn. Each pick is a linear scan, so it's `O(n * k)`.
+///
+/// # Panics
+/// If a weight is negative or not finite, or `k` exceeds the number of positive weights
+///
+/// # Example
+/// ```
+/// use hel_random::weighted_sample_without_replacement;
+///
+/// let skill = [5.0, 1.0, 0.0, 3.0, 2.0];
+/// let team = weighted_sample_without_replacement(&skill, 3);
+///
+/// assert_eq!(team.len(), 3);
+/// assert!(!team.contains(&2));
+/// ```
+pub fn weighted_sample_without_replacement(weights: &[f64], k: usize) -> Vec<usize> {
+	weighted_sample_without_replacement_from(&mut Global, weights, k)
+}
+
+pub(crate) fn weighted_sample_without_replacement_from<S: Source>(
+	source: &mut S,
+	weights: &[f64],
+	k: usize,
+) -> Vec<usize> {
+	assert!(
+		weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+		"weights must be finite and non-negative"
+	);
+
+	let positive = weights.iter().filter(|&&w| w > 0.0).count();
+	assert!(
+		k <= positive,
+		"can't pick {k} items out of {positive} with positive weight"
+	);
+
+	let mut weights = weights.to_vec();
+	let mut total: f64 = weights.iter().sum();
+	let mut picks = Vec::with_capacity(k);
+
+	for _ in 0..k {
+		let mut target = f64::random_from(source) * total;
+		let mut pick = None;
+
+		for (i, &w) in weights.iter().enumerate() {
+			if w <= 0.0 {
+				continue;
+			}
+
+			// Falls back to the last positive weight if rounding left `target` past the end
+			pick = Some(i);
+
+			if target < w {
+				break;
+			}
+
+			target -= w;
+		}
+
+		let pick = pick.expect("a positive weight is left");
+
+		total -= weights[pick];
+		weights[pick] = 0.0;
+		picks.push(pick);
+	}
+
+	picks
+}
+
+/// Will return a clone of a label picked proportionally to its weight
+///
+/// Saves mapping an index back to the data for the usual "pick an outcome by weight" case.
+/// Zero weights are never picked. Each draw is a linear scan over `table`.
+///
+/// Returns `None` if `table` is empty or the weights are all zero
+///
+/// # Panics
+/// If a weight is negative or not finite
+///
+/// # Example
+/// ```
+/// use hel_random::sample_labeled;
+///
+/// let rewards = [("gold", 70.0), ("gem", 25.0), ("relic", 5.0), ("nothing", 0.0)];
+/// let reward = sample_labeled(&rewards).unwrap();
+///
+/// assert_ne!(reward, "nothing");
+/// assert_eq!(sample_labeled::<&str>(&[]), None);
+/// ```
+pub fn sample_labeled<T: Clone>(table: &[(T, f64)]) -> Option<T> {
+	sample_labeled_from(&mut Global, table)
+}
+
+pub(crate) fn sample_labeled_from<S: Source, T: Clone>(
+	source: &mut S,
+	table: &[(T, f64)],
+) -> Option<T> {
+	assert!(
+		table.iter().all(|(_, w)| w.is_finite() && *w >= 0.0),
+		"weights must be finite and non-negative"
+	);
+
+	let total: f64 = table.iter().map(|(_, w)| w).sum();
+	if total <= 0.0 {
+		return None;
+	}
+
+	let mut target = f64::random_from(source) * total;
+	let mut pick = None;
+
+	for (label, w) in table {
+		if *w <= 0.0 {
+			continue;
+		}
+
+		// Falls back to the last positive weight if rounding left `target` past the end
+		pick = Some(label);
+
+		if target < *w {
+			break;
+		}
+
+		target -= w;
+	}
+
+	pick.cloned()
+}
+
+/// Will return a bucket index picked proportionally to its integer count, or [`None`] if there are
+/// no counts or they are all zero
+///
+/// Everything is integer arithmetic, so the odds are exact even for huge counts where floats would round.
+/// The total is summed in a [`u128`], it can't overflow. Each draw is a linear scan.
+///
+/// # Example
+/// ```
+/// use hel_random::weighted_index_int;
+///
+/// let page_views = [1_000_000_007, 3, 0, 999_999_999_999];
+/// let page = weighted_index_int(&page_views).unwrap();
+///
+/// assert_ne!(page, 2);
+/// assert_eq!(weighted_index_int(&[0, 0]), None);
+/// ```
+pub fn weighted_index_int(weights: &[u64]) -> Option<usize> {
+	weighted_index_int_from(&mut Global, weights)
+}
+
+pub(crate) fn weighted_index_int_from<S: Source>(source: &mut S, weights: &[u64]) -> Option<usize> {
+	let total: u128 = weights.iter().map(|&w| w as u128).sum();
+
+	if total == 0 {
+		return None;
+	}
+
+	let mut target = below_u128_from(source, total);
+
+	for (i, &w) in weights.iter().enumerate() {
+		let w = w as u128;
+
+		if target < w {
+			return Some(i);
+		}
+
+		target -= w;
+	}
+
+	unreachable!("the target is below the total")
+}
+
+/// A prebuilt table for O(1) weighted index draws (Vose's alias method)
+///
+/// Building it is `O(n)`, afterwards every draw takes one bounded draw and one float,
+/// no matter how many weights there are.
+///
+/// # Example
+/// ```
+/// use hel_random::AliasTable;
+///
+/// let loot = AliasTable::new(&[70.0, 25.0, 5.0]);
+///
+/// let drop = loot.sample();
+/// assert!(drop < 3);
+///
+/// let rare = loot.iter().take(1000).filter(|&i| i == 2).count();
+/// println!("{rare} rare drops");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+	// Probability of keeping the column itself instead of its alias
+	prob: Vec<f64>,
+	alias: Vec<usize>,
+}
+
+impl AliasTable {
+	/// Will build a table drawing index `i` with probability `weights[i] / sum`
+	///
+	/// # Panics
+	/// If `weights` is empty, a weight is negative or not finite, or they are all zero
+	pub fn new(weights: &[f64]) -> Self {
+		assert!(!weights.is_empty(), "weights are empty");
+		assert!(
+			weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+			"weights must be finite and non-negative"
+		);
+
+		let n = weights.len();
+		let total: f64 = weights.iter().sum();
+		assert!(total > 0.0, "weights are all zero");
+
+		// Scaled so the average column holds exactly 1
+		let mut scaled: Vec<f64> = weights.iter().map(|w| w * n as f64 / total).collect();
+		let mut prob = vec![1.0; n];
+		let mut alias: Vec<usize> = (0..n).collect();
+
+		let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+			(0..n).partition(|&i| scaled[i] < 1.0);
+
+		while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+			small.pop();
+
+			prob[s] = scaled[s];
+			alias[s] = l;
+
+			// The large column gives away what fills up the small one
+			scaled[l] -= 1.0 - scaled[s];
+
+			if scaled[l] < 1.0 {
+				large.pop();
+				small.push(l);
+			}
+		}
+
+		// Whatever is left is 1 up to rounding, it keeps itself
+		Self { prob, alias }
+	}
+
+	/// Will return the number of weights the table was built from
+	pub fn len(&self) -> usize {
+		self.prob.len()
+	}
+
+	/// Always `false`, a table can't be built from no weights
+	pub fn is_empty(&self) -> bool {
+		self.prob.is_empty()
+	}
+
+	/// Will draw an index from the global generator
+	#[inline]
+	pub fn sample(&self) -> usize {
+		self.sample_from(&mut Global)
+	}
+
+	/// Will draw an index from `source`
+	#[inline]
+	pub fn sample_from<S: Source>(&self, source: &mut S) -> usize {
+		let i = source.below(self.len() as u64) as usize;
+
+		if f64::random_from(source) < self.prob[i] {
+			i
+		} else {
+			self.alias[i]
+		}
+	}
+
+	/// Will return an endless iterator of indices drawn from the global generator
+	///
+	/// # Example
+	/// ```
+	/// use hel_random::AliasTable;
+	///
+	/// let table = AliasTable::new(&[1.0, 0.0, 1.0]);
+	///
+	/// assert!(table.iter().take(100).all(|i| i != 1));
+	/// ```
+	pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		std::iter::repeat_with(|| self.sample())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Every sampler is checked against these, `cdf_frequencies` as a cdf
+	const WEIGHTS: [f64; 4] = [1.0, 0.0, 6.0, 3.0];
+
+	// Draws a lot of indices and checks their shares against `WEIGHTS`
+	fn assert_frequencies(mut draw: impl FnMut() -> usize) {
+		const TRIES: usize = 100_000;
+
+		let mut counts = [0usize; 4];
+
+		for _ in 0..TRIES {
+			counts[draw()] += 1;
+		}
+
+		println!("Counts: {counts:?}");
+		assert_eq!(counts[1], 0);
+
+		let total: f64 = WEIGHTS.iter().sum();
+		for (&c, w) in counts.iter().zip(WEIGHTS) {
+			assert!((c as f64 / TRIES as f64 - w / total).abs() < 0.01);
+		}
+	}
+
+	#[test]
+	fn cdf_frequencies() {
+		assert_frequencies(|| sample_cdf(&[0.1, 0.1, 0.7, 1.0]));
+	}
+
+	#[test]
+	fn alias_frequencies() {
+		let table = AliasTable::new(&WEIGHTS);
+		assert_eq!(table.len(), 4);
+
+		let mut draws = table.iter();
+		assert_frequencies(|| draws.next().unwrap());
+
+		// A table rebuilt for every draw, from scaled weights, samples the same
+		assert_frequencies(|| AliasTable::new(&WEIGHTS.map(|w| w * 1e6)).sample());
+
+		assert!(AliasTable::new(&[5.0]).iter().take(10).all(|i| i == 0));
+	}
+
+	#[test]
+	fn labeled_frequencies() {
+		let labels = ["common", "never", "rare", "uncommon"].map(String::from);
+		let table: Vec<(String, f64)> = labels.iter().cloned().zip(WEIGHTS).collect();
+
+		assert_frequencies(|| {
+			let label = sample_labeled(&table).unwrap();
+			labels.iter().position(|l| *l == label).unwrap()
+		});
+
+		assert_eq!(sample_labeled::<char>(&[]), None);
+		assert_eq!(sample_labeled(&[('a', 0.0)]), None);
+	}
+
+	#[test]
+	fn int_weights_are_exact() {
+		// The same shares with a total of exactly `u64::MAX`, way past what an `f64` holds exactly
+		let unit = u64::MAX / 10;
+		let weights = [unit, 0, 6 * unit, 3 * unit + u64::MAX % 10];
+		assert_eq!(
+			weights.iter().map(|&w| w as u128).sum::<u128>(),
+			u64::MAX as u128
+		);
+
+		assert_frequencies(|| weighted_index_int(&weights).unwrap());
+
+		// The sum doesn't fit in a `u64`
+		let huge = [u64::MAX, u64::MAX, 0];
+		assert!((0..1_000).all(|_| weighted_index_int(&huge).unwrap() < 2));
+
+		assert_eq!(weighted_index_int(&[]), None);
+		assert_eq!(weighted_index_int(&[0, 5]), Some(1));
+	}
+
+	#[test]
+	#[cfg(feature = "test-hooks")]
+	fn int_weights_keep_the_last_unit() {
+		use crate::test_utils::set_next_values;
+
+		// A weight of 1 next to `u64::MAX - 1` is lost in floats, the top draw lands on it
+		let weights = [u64::MAX - 1, 1];
+
+		set_next_values(vec![u64::MAX, u64::MAX - 1]);
+		assert_eq!(weighted_index_int(&weights), Some(1));
+		assert_eq!(weighted_index_int(&weights), Some(0));
+	}
+
+	#[test]
+	fn seeded_helpers() {
+		let run = || {
+			let mut rng = crate::Rng::from_seed(13);
+
+			(
+				rng.sample_cdf(&[0.1, 0.1, 0.7, 1.0]),
+				rng.weighted_sample_without_replacement(&WEIGHTS, 3),
+				rng.sample_labeled(&[('a', 1.0), ('b', 6.0), ('c', 3.0)]),
+				rng.weighted_index_int(&[1, 0, 6, 3]),
+				rng.weighted_index_int(&[u64::MAX, u64::MAX, 1]),
+			)
+		};
+
+		assert_eq!(run(), run());
+	}
+
+	#[test]
+	#[should_panic]
+	fn alias_all_zero() {
+		AliasTable::new(&[0.0, 0.0]);
+	}
+
+	#[test]
+	fn without_replacement() {
+		const TRIES: usize = 20_000;
+
+		let weights = [1.0, 0.0, 2.0, 7.0];
+		let mut firsts = [0usize; 4];
+
+		for _ in 0..TRIES {
+			let picks = weighted_sample_without_replacement(&weights, 3);
+
+			let mut sorted = picks.clone();
+			sorted.sort();
+			assert_eq!(sorted, [0, 2, 3]);
+
+			firsts[picks[0]] += 1;
+		}
+
+		// The first pick is plain weighted sampling
+		let freq = firsts.map(|c| c as f64 / TRIES as f64);
+		println!("First picks: {freq:?}");
+		assert!((freq[0] - 0.1).abs() < 0.02);
+		assert!((freq[3] - 0.7).abs() < 0.02);
+
+		assert!(weighted_sample_without_replacement(&[], 0).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn without_replacement_too_many() {
+		weighted_sample_without_replacement(&[1.0, 0.0, 1.0], 3);
+	}
+
+	#[test]
+	#[should_panic]
+	fn labeled_negative() {
+		sample_labeled(&[('a', 1.0), ('b', -1.0)]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn labeled_nan() {
+		sample_labeled(&[('a', f64::NAN)]);
+	}
+
+	#[test]
+	fn cdf_short_of_one() {
+		for _ in 0..1_000 {
+			assert!(sample_cdf(&[0.5, 0.999_999]) < 2);
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	#[cfg(debug_assertions)]
+	fn cdf_decreasing() {
+		sample_cdf(&[0.5, 0.2, 1.0]);
+	}
+}
@@ -0,0 +1,21 @@
+//! The global stream checked against the reference xoshiro256++ seeded with the reference SplitMix64
+//!
+//! Lives in its own binary, the unit tests draw from the global generator concurrently
+
+#[test]
+fn seeded_global_matches_reference() {
+	let expected: [u64; 6] = [
+		0x0c520eb8fea98ede,
+		0x2b74a6338b80e0e2,
+		0xbe238770c3795322,
+		0x5f235f98a244ea97,
+		0xe004f0cc1514d858,
+		0x436a209963ff9223,
+	];
+
+	hel_random::seed(0xdeadbeef);
+
+	for e in expected {
+		assert_eq!(hel_random::u64(), e);
+	}
+}